@@ -1,59 +1,208 @@
+mod report;
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::error::Error;
-use std::{env, fs};
+use std::fmt;
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader};
 use std::process::exit;
+use crate::report::Report;
+
+#[derive(Debug)]
+pub enum InventoryError {
+    Io(io::Error),
+    InvalidNumber { line: usize, value: String },
+    Overflow { line: usize },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::Io(e) => write!(f, "Error while reading the inventory: {}", e),
+            InventoryError::InvalidNumber { line, value } => write!(f, "Invalid calories {:?} on line {}", value, line),
+            InventoryError::Overflow { line } => write!(f, "Calories total overflow on line {}", line),
+        }
+    }
+}
+
+impl Error for InventoryError {}
+
+#[derive(Default, Debug)]
+pub struct Elves {
+    pub totals: Vec<i64>,
+    pub items: Vec<usize>,
+}
+
+// Ranked (elf index, total) pairs, greatest first, only keeping k candidates in the heap
+fn top_k(totals: impl IntoIterator<Item = i64>, k: usize) -> Vec<(usize, i64)> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for (elf, total) in totals.into_iter().enumerate() {
+        heap.push(Reverse((total, Reverse(elf))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
 
-#[derive(Default)]
-struct Elves {
-    pub elves: Vec<Vec<i32>>,
+    heap.into_sorted_vec().into_iter()
+        .map(|Reverse((total, Reverse(elf)))| (elf, total))
+        .collect()
 }
 
 impl Elves {
-    fn parse_string(input: String) -> Result<Self, Box<dyn Error>> {
+    // Only the running sum of the current elf is kept while reading. Elves are separated by
+    // one or more blank (or whitespace-only) lines, and the last one needs no trailing separator.
+    fn parse_reader(reader: impl BufRead) -> Result<Self, InventoryError> {
         let mut calories = Elves::default();
-        let mut acc = Vec::new();
-        for line in input.lines() {
+        let mut acc: i64 = 0;
+        let mut items = 0;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(InventoryError::Io)?;
+            let line = line.trim();
             if line.is_empty() {
-                calories.elves.push(acc);
-                acc = Vec::new();
+                if items > 0 {
+                    calories.totals.push(acc);
+                    calories.items.push(items);
+                }
+                acc = 0;
+                items = 0;
             } else {
-                let snack = line.trim().parse::<i32>()?;
-                acc.push(snack);
+                let snack = line.parse::<i64>().ok()
+                    .filter(|snack| *snack >= 0)
+                    .ok_or_else(|| InventoryError::InvalidNumber { line: i + 1, value: line.to_string() })?;
+                acc = acc.checked_add(snack).ok_or(InventoryError::Overflow { line: i + 1 })?;
+                items += 1;
             }
         }
+        if items > 0 {
+            calories.totals.push(acc);
+            calories.items.push(items);
+        }
 
         Ok(calories)
     }
 
-    fn solve_part_1(&self) -> i32 {
-        self.elves.iter().map(|elf| {
-            elf.iter().sum()
-        }).max().unwrap()
+    fn top_k(&self, k: usize) -> Vec<(usize, i64)> {
+        top_k(self.totals.iter().copied(), k)
     }
 
-    fn solve_part_2(&self) -> i32 {
-        let mut sum_elves : Vec<i32> = self.elves.iter().map(|elf| {
-            elf.iter().sum()
-        }).collect();
-        sum_elves.sort();
-        sum_elves.reverse();
+    fn solve_part_1(&self) -> i64 {
+        self.top_k(1).first().map_or(0, |(_, total)| *total)
+    }
 
-        sum_elves[0] + sum_elves[1] + sum_elves[2]
+    // Summed in 128 bits, as the three totals can each be up to i64::MAX
+    fn solve_part_2(&self) -> i128 {
+        self.top_k(3).iter().map(|(_, total)| *total as i128).sum()
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
+    if args.len() != 2 && args.len() != 3 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
-    let file_content =
-        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
-    let input = Elves::parse_string(file_content)?;
+    let input = Elves::parse_reader(BufReader::new(File::open(&args[1])?))?;
 
-    println!("part 1: {}", input.solve_part_1());
-    println!("part 2: {}", input.solve_part_2());
+    match args.get(2).map(|format| format.as_str()) {
+        None => {
+            println!("part 1: {}", input.solve_part_1());
+            println!("part 2: {}", input.solve_part_2());
+        }
+        Some("csv") => print!("{}", Report::new(&input).to_csv()),
+        Some("json") => println!("{}", Report::new(&input).to_json()),
+        Some(format) => {
+            eprintln!("Unknown report format {}, expected csv or json", format);
+            exit(1);
+        }
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{top_k, Elves, InventoryError};
+    use crate::report::Report;
+
+    const SAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+
+";
+
+    #[test]
+    fn sample() {
+        let elves = Elves::parse_reader(SAMPLE.as_bytes()).unwrap();
+
+        assert_eq!(elves.top_k(3), vec![(3, 24000), (2, 11000), (4, 10000)]);
+        assert_eq!(elves.solve_part_1(), 24000);
+        assert_eq!(elves.solve_part_2(), 45000);
+    }
+
+    #[test]
+    fn top_k_ties_and_small_inputs() {
+        assert_eq!(top_k([5, 7, 7, 1], 2), vec![(1, 7), (2, 7)]);
+        assert_eq!(top_k([5], 3), vec![(0, 5)]);
+        assert_eq!(top_k([5, 7], 0), vec![]);
+    }
+
+    #[test]
+    fn report() {
+        let elves = Elves::parse_reader(SAMPLE.as_bytes()).unwrap();
+        let report = Report::new(&elves);
+
+        assert_eq!(report.elves.iter().map(|e| e.items).collect::<Vec<_>>(), vec![3, 1, 2, 3, 1]);
+        assert_eq!(report.mean, 11000.0);
+        assert_eq!(report.median, 10000.0);
+        assert_eq!(report.percentiles[1], (25, 6000.0));
+        assert_eq!(report.histogram.iter().map(|b| b.count).sum::<usize>(), 5);
+        assert!(report.to_csv().starts_with("elf,items,total,outlier\n0,3,6000,false\n"));
+        assert!(report.to_json().ends_with("\"outliers\":[3]}"));
+
+        let elves = Elves::parse_reader(format!("0\n\n{}\n", i64::MAX).as_bytes()).unwrap();
+        let report = Report::new(&elves);
+        let histogram = &report.histogram;
+        assert_eq!(histogram.len(), 10);
+        assert_eq!((histogram[0].start, histogram[0].count), (0, 1));
+        assert_eq!((histogram[9].end, histogram[9].count), (i64::MAX, 1));
+        assert!(histogram.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(report.to_csv().contains(&i64::MAX.to_string()));
+    }
+
+    #[test]
+    fn robust_parsing() {
+        let elves = Elves::parse_reader("1\r\n2\r\n \t\r\n\r\n3\r\n4".as_bytes()).unwrap();
+        assert_eq!(elves.totals, vec![3, 7]);
+        assert_eq!(elves.items, vec![2, 2]);
+
+        let error = Elves::parse_reader("1\n\n2\nx3\n".as_bytes()).unwrap_err();
+        assert!(matches!(error, InventoryError::InvalidNumber { line: 4, .. }));
+
+        let error = Elves::parse_reader(format!("1\n\n{}\n{}\n", i64::MAX, 1).as_bytes()).unwrap_err();
+        assert!(matches!(error, InventoryError::Overflow { line: 4 }));
+    }
+
+    #[test]
+    fn large_totals() {
+        let large = 1i64 << 62;
+        let elves = Elves::parse_reader(format!("{}\n\n{}\n\n{}\n", large, large, i64::MAX).as_bytes()).unwrap();
+
+        assert_eq!(elves.solve_part_1(), i64::MAX);
+        assert_eq!(elves.solve_part_2(), i64::MAX as i128 + 2 * large as i128);
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use crate::Elves;
+
+const PERCENTILES: [u32; 5] = [10, 25, 75, 90, 99];
+const HISTOGRAM_BUCKETS: i64 = 10;
+
+pub struct ElfSummary {
+    pub elf: usize,
+    pub items: usize,
+    pub total: i64,
+    pub outlier: bool,
+}
+
+// Totals in start..end, the end of the last bucket saturating at i64::MAX
+pub struct Bucket {
+    pub start: i64,
+    pub end: i64,
+    pub count: usize,
+}
+
+pub struct Report {
+    pub elves: Vec<ElfSummary>,
+    pub mean: f64,
+    pub median: f64,
+    pub percentiles: Vec<(u32, f64)>,
+    pub histogram: Vec<Bucket>,
+}
+
+// Linear interpolation between the closest ranks
+fn percentile(sorted: &[i64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] as f64 + (sorted[high] as f64 - sorted[low] as f64) * (rank - low as f64)
+}
+
+fn histogram(sorted: &[i64]) -> Vec<Bucket> {
+    let (min, max) = match (sorted.first(), sorted.last()) {
+        (Some(&min), Some(&max)) => (min, max),
+        _ => return vec![],
+    };
+    // In 128 bits, as the totals can span the whole i64 range
+    let (min, max) = (min as i128, max as i128);
+    let width = ((max - min + 1) + HISTOGRAM_BUCKETS as i128 - 1) / HISTOGRAM_BUCKETS as i128;
+
+    let mut buckets = (0..(max - min) / width + 1)
+        .map(|i| Bucket {
+            start: (min + i * width) as i64,
+            end: (min + (i + 1) * width).min(i64::MAX as i128) as i64,
+            count: 0,
+        })
+        .collect::<Vec<_>>();
+    for &total in sorted {
+        buckets[((total as i128 - min) / width) as usize].count += 1;
+    }
+
+    buckets
+}
+
+impl Report {
+    pub fn new(elves: &Elves) -> Self {
+        let mut sorted = elves.totals.clone();
+        sorted.sort();
+
+        // Tukey's fences
+        let q1 = percentile(&sorted, 25.0);
+        let q3 = percentile(&sorted, 75.0);
+        let low_fence = q1 - 1.5 * (q3 - q1);
+        let high_fence = q3 + 1.5 * (q3 - q1);
+
+        let summaries = elves.totals.iter().zip(&elves.items).enumerate()
+            .map(|(elf, (&total, &items))| ElfSummary {
+                elf,
+                items,
+                total,
+                outlier: (total as f64) < low_fence || (total as f64) > high_fence,
+            })
+            .collect();
+
+        let mean = if sorted.is_empty() {
+            0.0
+        } else {
+            sorted.iter().map(|&t| t as f64).sum::<f64>() / sorted.len() as f64
+        };
+
+        Report {
+            elves: summaries,
+            mean,
+            median: percentile(&sorted, 50.0),
+            percentiles: PERCENTILES.iter().map(|&p| (p, percentile(&sorted, p as f64))).collect(),
+            histogram: histogram(&sorted),
+        }
+    }
+
+    fn outliers(&self) -> Vec<usize> {
+        self.elves.iter().filter(|e| e.outlier).map(|e| e.elf).collect()
+    }
+
+    // One table per section, separated by an empty line
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("elf,items,total,outlier\n");
+        for e in &self.elves {
+            writeln!(csv, "{},{},{},{}", e.elf, e.items, e.total, e.outlier).unwrap();
+        }
+
+        csv.push_str("\nstatistic,value\n");
+        writeln!(csv, "mean,{}", self.mean).unwrap();
+        writeln!(csv, "median,{}", self.median).unwrap();
+        for (p, v) in &self.percentiles {
+            writeln!(csv, "p{},{}", p, v).unwrap();
+        }
+
+        csv.push_str("\nbucket_start,bucket_end,count\n");
+        for b in &self.histogram {
+            writeln!(csv, "{},{},{}", b.start, b.end, b.count).unwrap();
+        }
+
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let elves = self.elves.iter()
+            .map(|e| format!("{{\"elf\":{},\"items\":{},\"total\":{},\"outlier\":{}}}", e.elf, e.items, e.total, e.outlier))
+            .collect::<Vec<_>>();
+        let percentiles = self.percentiles.iter()
+            .map(|(p, v)| format!("\"p{}\":{}", p, v))
+            .collect::<Vec<_>>();
+        let histogram = self.histogram.iter()
+            .map(|b| format!("{{\"start\":{},\"end\":{},\"count\":{}}}", b.start, b.end, b.count))
+            .collect::<Vec<_>>();
+        let outliers = self.outliers().iter().map(|o| o.to_string()).collect::<Vec<_>>();
+
+        format!(
+            "{{\"elves\":[{}],\"mean\":{},\"median\":{},\"percentiles\":{{{}}},\"histogram\":[{}],\"outliers\":[{}]}}",
+            elves.join(","),
+            self.mean,
+            self.median,
+            percentiles.join(","),
+            histogram.join(","),
+            outliers.join(","),
+        )
+    }
+}
@@ -0,0 +1,116 @@
+use crate::game::{Outcome, Shape, OUTCOMES};
+use crate::{response_symbols, EncryptedStrategyGuide, OUTCOME_SYMBOLS};
+
+// Meaning of each symbol of the second column, in alphabetical order
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cipher {
+    Shapes(Vec<Shape>),
+    Outcomes(Vec<Outcome>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ScoredCipher {
+    pub cipher: Cipher,
+    pub score: u64,
+}
+
+// Official, best and worst meanings of the second column for one kind of cipher
+#[derive(Debug)]
+pub struct CipherSummary {
+    pub official: ScoredCipher,
+    pub best: ScoredCipher,
+    pub worst: ScoredCipher,
+    pub count: usize,
+}
+
+fn permutations<T: Copy>(items: &[T]) -> Vec<Vec<T>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+
+    let mut result = Vec::new();
+    for i in 0..items.len() {
+        let mut rest = items.to_vec();
+        let first = rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, first);
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+impl Cipher {
+    fn score(&self, guide: &EncryptedStrategyGuide) -> Option<u64> {
+        match self {
+            Cipher::Shapes(shapes) => Some(guide.score_shapes(shapes)),
+            Cipher::Outcomes(outcomes) => guide.score_outcomes(outcomes),
+        }
+    }
+
+    pub fn describe(&self, guide: &EncryptedStrategyGuide) -> String {
+        let meanings = match self {
+            Cipher::Shapes(shapes) => response_symbols(&guide.game).iter().zip(shapes)
+                .map(|(c, s)| format!("{}={}", c, guide.game.name(*s)))
+                .collect::<Vec<_>>(),
+            Cipher::Outcomes(outcomes) => OUTCOME_SYMBOLS.iter().zip(outcomes)
+                .map(|(c, o)| format!("{}={:?}", c, o))
+                .collect::<Vec<_>>(),
+        };
+        meanings.join(" ")
+    }
+}
+
+// Ties keep the first cipher in enumeration order
+fn summarize(guide: &EncryptedStrategyGuide, official: Cipher, candidates: Vec<Cipher>) -> Option<CipherSummary> {
+    let official = ScoredCipher { score: official.score(guide)?, cipher: official };
+    let scored = candidates.into_iter()
+        .filter_map(|cipher| Some(ScoredCipher { score: cipher.score(guide)?, cipher }))
+        .collect::<Vec<_>>();
+
+    let mut best = &official;
+    let mut worst = &official;
+    for c in &scored {
+        if c.score > best.score {
+            best = c;
+        }
+        if c.score < worst.score {
+            worst = c;
+        }
+    }
+
+    Some(CipherSummary {
+        best: best.clone(),
+        worst: worst.clone(),
+        official,
+        count: scored.len(),
+    })
+}
+
+// Scores the guide under every mapping of the second column to shapes, then to outcomes.
+// Outcome ciphers are skipped when the guide uses more symbols than X, Y and Z.
+pub fn discover(guide: &EncryptedStrategyGuide) -> Vec<CipherSummary> {
+    let shapes = guide.game.shapes().collect::<Vec<_>>();
+    let shape_ciphers = permutations(&shapes).into_iter().map(Cipher::Shapes).collect();
+    let outcome_ciphers = permutations(&OUTCOMES).into_iter().map(Cipher::Outcomes).collect();
+
+    [
+        summarize(guide, Cipher::Shapes(shapes), shape_ciphers),
+        summarize(guide, Cipher::Outcomes(OUTCOMES.to_vec()), outcome_ciphers),
+    ].into_iter().flatten().collect()
+}
+
+impl CipherSummary {
+    pub fn table(&self, guide: &EncryptedStrategyGuide) -> String {
+        let kind = match self.official.cipher {
+            Cipher::Shapes(_) => "shape",
+            Cipher::Outcomes(_) => "outcome",
+        };
+
+        let mut table = format!("{} ciphers ({} scored)\n", kind, self.count);
+        for (label, c) in [("official", &self.official), ("best", &self.best), ("worst", &self.worst)] {
+            table.push_str(&format!("{:<10}{:>10}  {}\n", label, c.score, c.cipher.describe(guide)));
+        }
+        table
+    }
+}
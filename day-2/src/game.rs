@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Shape(pub usize);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Loose,
+    Draw,
+    Win,
+}
+
+pub const OUTCOMES: [Outcome; 3] = [Outcome::Loose, Outcome::Draw, Outcome::Win];
+
+impl Outcome {
+    pub fn score(&self) -> u64 {
+        match self {
+            Outcome::Loose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShapeDefinition {
+    pub name: String,
+    pub score: u64,
+}
+
+// Hand game where the shapes, their scores and which shape beats which are data.
+// Pairs of distinct shapes missing from the relation are draws.
+#[derive(Debug)]
+pub struct Game {
+    shapes: Vec<ShapeDefinition>,
+    // beats[a][b] is true when shape a beats shape b
+    beats: Vec<Vec<bool>>,
+}
+
+impl Game {
+    pub fn new(shapes: &[(&str, u64)], beats: &[(usize, usize)]) -> Result<Self, Box<dyn Error>> {
+        let mut relation = vec![vec![false; shapes.len()]; shapes.len()];
+        for &(a, b) in beats {
+            if a >= shapes.len() || b >= shapes.len() {
+                return Err(format!("Unknown shape in ({}, {})", a, b).into());
+            }
+            if a == b || relation[b][a] {
+                return Err(format!("Shapes {} and {} can't beat each other", shapes[a].0, shapes[b].0).into());
+            }
+            relation[a][b] = true;
+        }
+
+        Ok(Game {
+            shapes: shapes.iter().map(|&(name, score)| ShapeDefinition { name: name.to_string(), score }).collect(),
+            beats: relation,
+        })
+    }
+
+    // Balanced tournament of an odd number of shapes: each one beats the (n - 1) / 2 shapes before it
+    pub fn cyclic(shapes: &[(&str, u64)]) -> Result<Self, Box<dyn Error>> {
+        let n = shapes.len();
+        if n.is_multiple_of(2) {
+            return Err(format!("A cyclic game needs an odd number of shapes, got {}", n).into());
+        }
+
+        let beats = (0..n)
+            .flat_map(|a| (1..=n / 2).map(move |k| (a, (a + n - k) % n)))
+            .collect::<Vec<_>>();
+        Game::new(shapes, &beats)
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Game::cyclic(&[("Rock", 1), ("Paper", 2), ("Scissors", 3)]).unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Game::cyclic(&[("Rock", 1), ("Spock", 2), ("Paper", 3), ("Lizard", 4), ("Scissors", 5)]).unwrap()
+    }
+
+    pub fn shape_count(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].name
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> u64 {
+        self.shapes[shape.0].score
+    }
+
+    // Outcome of the round for the player
+    pub fn outcome(&self, opponent: Shape, player: Shape) -> Outcome {
+        if self.beats[player.0][opponent.0] {
+            Outcome::Win
+        } else if self.beats[opponent.0][player.0] {
+            Outcome::Loose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn round_score(&self, opponent: Shape, player: Shape) -> u64 {
+        self.outcome(opponent, player).score()
+    }
+
+    // First shape in table order giving the wanted outcome
+    pub fn shape_for(&self, opponent: Shape, outcome: Outcome) -> Option<Shape> {
+        self.shapes().find(|&player| self.outcome(opponent, player) == outcome)
+    }
+}
+
+// One line per shape, with its score and the shapes it beats
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for a in self.shapes() {
+            let beaten = self.shapes()
+                .filter(|&b| self.beats[a.0][b.0])
+                .map(|b| self.name(b))
+                .collect::<Vec<_>>();
+            writeln!(f, "{} ({}) beats {}", self.name(a), self.shape_score(a), beaten.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,150 +1,248 @@
+mod cipher;
+mod game;
+mod tournament;
+
 use std::{env, fs};
 use std::error::Error;
 use std::process::exit;
+use crate::cipher::discover;
+use crate::game::{Game, Outcome, Shape, OUTCOMES};
+use crate::tournament::{round_robin, FrequencyPlayer, GuidePlayer, OraclePlayer, Player, RandomPlayer};
 
-#[derive(Debug, Copy, Clone)]
-enum Action {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl Action {
-    fn from_string(string: &str) -> Self {
-        match string {
-            "A" | "X" => Action::Rock,
-            "B" | "Y" => Action::Paper,
-            "C" | "Z" => Action::Scissors,
-            _ => panic!("Unknown pattern {}", string)
-        }
-    }
+const OUTCOME_SYMBOLS: [char; 3] = ['X', 'Y', 'Z'];
 
-    fn shape_score(&self) -> u64 {
-        match self {
-            Action::Rock => 1,
-            Action::Paper => 2,
-            Action::Scissors => 3,
-        }
-    }
-}
-
-#[derive(Debug)]
-enum Outcome {
-    Loose,
-    Draw,
-    Win,
-}
-
-impl Outcome {
-    fn from_string(string: &str) -> Self {
-        match string {
-            "X" => Outcome::Loose,
-            "Y" => Outcome::Draw,
-            "Z" => Outcome::Win,
-            _ => panic!("Unknown pattern {}", string)
-        }
-    }
-
-    fn score(&self) -> u64 {
-        match self {
-            Outcome::Loose => 0,
-            Outcome::Draw => 3,
-            Outcome::Win => 6,
-        }
-    }
-
-    fn action_to_play(&self, a: &Action) -> Action {
-        match self {
-            Outcome::Loose => {
-                match a {
-                    Action::Rock => Action::Scissors,
-                    Action::Paper => Action::Rock,
-                    Action::Scissors => Action::Paper,
-                }
-            }
-            Outcome::Draw => *a,
-            Outcome::Win => {
-                match a {
-                    Action::Rock => Action::Paper,
-                    Action::Paper => Action::Scissors,
-                    Action::Scissors => Action::Rock,
-                }
-            }
-        }
-    }
-}
-
-fn round_score(a: &Action, b: &Action) -> u64 {
-    match a {
-        Action::Rock => {
-            match b {
-                Action::Rock => 3,
-                Action::Paper => 6,
-                Action::Scissors => 0
-            }
-        }
-        Action::Paper => {
-            match b {
-                Action::Rock => 0,
-                Action::Paper => 3,
-                Action::Scissors => 6
-            }
-        }
-        Action::Scissors => {
-            match b {
-                Action::Rock => 6,
-                Action::Paper => 0,
-                Action::Scissors => 3
-            }
-        }
-    }
+// The second column uses the last letters of the alphabet, X, Y and Z for three shapes
+fn response_symbols(game: &Game) -> Vec<char> {
+    (b'Z' + 1 - game.shape_count() as u8..=b'Z').map(|c| c as char).collect()
 }
 
 #[derive(Debug)]
 struct EncryptedStrategyGuide {
-    guide: Vec<(Action, Action, Outcome)>,
+    game: Game,
+    guide: Vec<(Shape, char)>,
 }
 
 impl EncryptedStrategyGuide {
-    fn parse_data(data: String) -> Self {
+    fn parse_data(game: Game, data: String) -> Self {
+        let responses = response_symbols(&game);
         let guide = data.lines().map(|line| {
             let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
-            (Action::from_string(parts[0]), Action::from_string(parts[1]), Outcome::from_string(parts[1]))
+            let opponent = parts[0].chars().next()
+                .map(|c| (c as usize).wrapping_sub('A' as usize))
+                .filter(|&i| i < game.shape_count())
+                .unwrap_or_else(|| panic!("Unknown pattern {}", parts[0]));
+            let response = parts[1].chars().next()
+                .filter(|c| responses.contains(c))
+                .unwrap_or_else(|| panic!("Unknown pattern {}", parts[1]));
+            (Shape(opponent), response)
         })
             .collect();
 
         EncryptedStrategyGuide {
+            game,
             guide
         }
     }
 
-    fn solve_part_1(&self) -> u64 {
-        self.guide.iter().map(|(a, b, _)| {
-            b.shape_score() + round_score(a, b)
+    // Score when the second column means the shape to play
+    fn score_shapes(&self, shapes: &[Shape]) -> u64 {
+        let responses = response_symbols(&self.game);
+        self.guide.iter().map(|(a, r)| {
+            let b = shapes[responses.iter().position(|c| c == r).unwrap()];
+            self.game.shape_score(b) + self.game.round_score(*a, b)
         }).sum()
     }
 
-    fn solve_part_2(&self) -> u64 {
-        self.guide.iter().map(|(a, _, o)| {
-            o.action_to_play(a).shape_score() + o.score()
+    // Score when the second column means the outcome to get, if all its symbols are outcome symbols
+    fn score_outcomes(&self, outcomes: &[Outcome]) -> Option<u64> {
+        self.guide.iter().map(|(a, r)| {
+            let o = outcomes[OUTCOME_SYMBOLS.iter().position(|c| c == r)?];
+            let b = self.game.shape_for(*a, o)?;
+            Some(self.game.shape_score(b) + o.score())
         }).sum()
     }
+
+    // Both columns as played shapes, with the official meaning of the second one
+    fn players(&self) -> (GuidePlayer, GuidePlayer) {
+        let responses = response_symbols(&self.game);
+        let opponent = self.guide.iter().map(|(a, _)| *a).collect();
+        let response = self.guide.iter()
+            .map(|(_, r)| Shape(responses.iter().position(|c| c == r).unwrap()))
+            .collect();
+
+        (GuidePlayer::new("guide opponent", opponent), GuidePlayer::new("guide response", response))
+    }
+
+    fn solve_part_1(&self) -> u64 {
+        self.score_shapes(&self.game.shapes().collect::<Vec<_>>())
+    }
+
+    fn solve_part_2(&self) -> u64 {
+        self.score_outcomes(&OUTCOMES).expect("The guide has symbols which are not outcomes")
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
+    if args.len() < 2 || args.len() > 5 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
     let file_content =
         fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
 
-    let guide = EncryptedStrategyGuide::parse_data(file_content);
+    let mut game = Game::rock_paper_scissors();
+    let mut ciphers = false;
+    let mut tournament = false;
+    for option in &args[2..] {
+        match option.as_str() {
+            "rps" => game = Game::rock_paper_scissors(),
+            "rpsls" => game = Game::rock_paper_scissors_lizard_spock(),
+            "ciphers" => ciphers = true,
+            "tournament" => tournament = true,
+            _ => {
+                eprintln!("Unknown option {}, expected rps, rpsls, ciphers or tournament", option);
+                exit(1);
+            }
+        }
+    }
+    let guide = EncryptedStrategyGuide::parse_data(game, file_content);
 
-    println!("part 1: {}", guide.solve_part_1());
-    println!("part 2: {}", guide.solve_part_2());
+    if ciphers {
+        for summary in discover(&guide) {
+            println!("{}", summary.table(&guide));
+        }
+    } else if tournament {
+        let (opponent, response) = guide.players();
+        let mut players: Vec<Box<dyn Player>> = vec![
+            Box::new(opponent),
+            Box::new(response),
+            Box::new(RandomPlayer::new(2022)),
+            Box::new(FrequencyPlayer::default()),
+            Box::new(OraclePlayer),
+        ];
+        for result in round_robin(&guide.game, &mut players, guide.guide.len()) {
+            println!("{} vs {}: {} - {} ({} wins, {} draws, {} losses)",
+                     result.players.0, result.players.1, result.scores.0, result.scores.1,
+                     result.wins.0, result.draws, result.wins.1);
+        }
+    } else {
+        println!("part 1: {}", guide.solve_part_1());
+        println!("part 2: {}", guide.solve_part_2());
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EncryptedStrategyGuide;
+    use crate::cipher::{discover, Cipher};
+    use crate::tournament::{play_match, round_robin, FrequencyPlayer, OraclePlayer, Player, RandomPlayer};
+    use crate::game::{Game, Outcome, Shape};
+
+    #[test]
+    fn sample() {
+        assert!(Game::rock_paper_scissors().to_string().starts_with("Rock (1) beats Scissors\n"));
+
+        let guide = EncryptedStrategyGuide::parse_data(Game::rock_paper_scissors(), "A Y
+B X
+C Z
+".to_string());
+
+        assert_eq!(guide.solve_part_1(), 15);
+        assert_eq!(guide.solve_part_2(), 12);
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name: &str| game.shapes().find(|&s| game.name(s) == name).unwrap();
+
+        assert_eq!(game.outcome(shape("Paper"), shape("Scissors")), Outcome::Win);
+        assert_eq!(game.outcome(shape("Spock"), shape("Lizard")), Outcome::Win);
+        assert_eq!(game.outcome(shape("Rock"), shape("Lizard")), Outcome::Loose);
+        assert_eq!(game.outcome(shape("Spock"), shape("Spock")), Outcome::Draw);
+        for a in game.shapes() {
+            assert_eq!(game.shapes().filter(|&b| game.outcome(a, b) == Outcome::Win).count(), 2);
+        }
+
+        let guide = EncryptedStrategyGuide::parse_data(game, "E V
+A Z
+".to_string());
+        // Rock beats Scissors, then Scissors looses to Rock
+        assert_eq!(guide.solve_part_1(), 1 + 6 + 5);
+        assert_eq!(guide.game.shape_for(Shape(0), Outcome::Win), Some(Shape(1)));
+    }
+
+    #[test]
+    fn invalid_relations() {
+        assert!(Game::cyclic(&[("A", 1), ("B", 2)]).is_err());
+        assert!(Game::new(&[("A", 1), ("B", 2)], &[(0, 1), (1, 0)]).is_err());
+    }
+
+    #[test]
+    fn ciphers() {
+        let guide = EncryptedStrategyGuide::parse_data(Game::rock_paper_scissors(), "A Y
+B X
+C Z
+".to_string());
+        let summaries = discover(&guide);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].official.score, 15);
+        assert_eq!(summaries[1].official.score, 12);
+        for summary in &summaries {
+            assert_eq!(summary.count, 6);
+            assert!(summary.best.score >= summary.official.score);
+            assert!(summary.worst.score <= summary.official.score);
+        }
+        assert_eq!(summaries[0].worst.score, 6);
+        assert_eq!(summaries[0].worst.cipher, Cipher::Shapes(vec![Shape(0), Shape(2), Shape(1)]));
+        assert_eq!(summaries[0].official.cipher.describe(&guide), "X=Rock Y=Paper Z=Scissors");
+
+        // V and W have no outcome meaning
+        let guide = EncryptedStrategyGuide::parse_data(Game::rock_paper_scissors_lizard_spock(), "A V
+".to_string());
+        assert_eq!(discover(&guide).len(), 1);
+    }
+
+    #[test]
+    fn tournament() {
+        let guide = EncryptedStrategyGuide::parse_data(Game::rock_paper_scissors(), "A Y
+B X
+C Z
+".to_string());
+        let (mut opponent, mut response) = guide.players();
+
+        let result = play_match(&guide.game, &mut opponent, &mut response, 3);
+        assert_eq!(result.scores.1, guide.solve_part_1());
+        assert_eq!((result.wins.1, result.draws, result.wins.0), (1, 1, 1));
+
+        let result = play_match(&guide.game, &mut OraclePlayer, &mut RandomPlayer::new(7), 100);
+        assert_eq!(result.wins.0, 100);
+        assert_eq!(play_match(&guide.game, &mut RandomPlayer::new(7), &mut opponent, 50),
+                   play_match(&guide.game, &mut RandomPlayer::new(7), &mut opponent, 50));
+
+        // Always Rock against a frequency counter: only the first round is lost
+        let mut frequency = FrequencyPlayer::default();
+        let result = play_match(&guide.game, &mut RockPlayer, &mut frequency, 10);
+        assert_eq!((result.wins.0, result.draws, result.wins.1), (0, 1, 9));
+
+        let mut players: Vec<Box<dyn Player>> = vec![Box::new(opponent), Box::new(response), Box::new(OraclePlayer)];
+        assert_eq!(round_robin(&guide.game, &mut players, 3).len(), 3);
+    }
+
+    struct RockPlayer;
+
+    impl Player for RockPlayer {
+        fn name(&self) -> String {
+            "rock".to_string()
+        }
+
+        fn play(&mut self, _game: &Game, _revealed: Option<Shape>) -> Shape {
+            Shape(0)
+        }
+    }
+}
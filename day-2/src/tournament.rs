@@ -0,0 +1,181 @@
+use crate::game::{Game, Outcome, Shape};
+
+pub trait Player {
+    fn name(&self) -> String;
+
+    // Peeking players are told the opponent shape of the round before choosing theirs
+    fn peeks(&self) -> bool {
+        false
+    }
+
+    fn play(&mut self, game: &Game, revealed: Option<Shape>) -> Shape;
+
+    fn observe(&mut self, _opponent: Shape) {}
+
+    fn reset(&mut self) {}
+}
+
+// Plays a fixed list of shapes, looping over it
+pub struct GuidePlayer {
+    name: String,
+    shapes: Vec<Shape>,
+    round: usize,
+}
+
+impl GuidePlayer {
+    pub fn new(name: &str, shapes: Vec<Shape>) -> Self {
+        GuidePlayer { name: name.to_string(), shapes, round: 0 }
+    }
+}
+
+impl Player for GuidePlayer {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn play(&mut self, _game: &Game, _revealed: Option<Shape>) -> Shape {
+        let shape = self.shapes[self.round % self.shapes.len()];
+        self.round += 1;
+        shape
+    }
+
+    fn reset(&mut self) {
+        self.round = 0;
+    }
+}
+
+// Uniform random shapes from a xorshift generator, so that a seed always gives the same match
+pub struct RandomPlayer {
+    seed: u64,
+    state: u64,
+}
+
+impl RandomPlayer {
+    pub fn new(seed: u64) -> Self {
+        let seed = seed.max(1);
+        RandomPlayer { seed, state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl Player for RandomPlayer {
+    fn name(&self) -> String {
+        format!("random({})", self.seed)
+    }
+
+    fn play(&mut self, game: &Game, _revealed: Option<Shape>) -> Shape {
+        Shape((self.next() % game.shape_count() as u64) as usize)
+    }
+
+    fn reset(&mut self) {
+        self.state = self.seed;
+    }
+}
+
+// Counters the shape the opponent played the most so far
+#[derive(Default)]
+pub struct FrequencyPlayer {
+    counts: Vec<usize>,
+}
+
+impl Player for FrequencyPlayer {
+    fn name(&self) -> String {
+        "frequency".to_string()
+    }
+
+    fn play(&mut self, game: &Game, _revealed: Option<Shape>) -> Shape {
+        let most_played = (0..self.counts.len())
+            .rev()
+            .max_by_key(|&s| self.counts[s])
+            .map(Shape);
+        most_played
+            .and_then(|s| game.shape_for(s, Outcome::Win))
+            .unwrap_or(Shape(0))
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        if self.counts.len() <= opponent.0 {
+            self.counts.resize(opponent.0 + 1, 0);
+        }
+        self.counts[opponent.0] += 1;
+    }
+
+    fn reset(&mut self) {
+        self.counts.clear();
+    }
+}
+
+// Cheats by looking at the opponent shape, and wins whenever the game allows it
+pub struct OraclePlayer;
+
+impl Player for OraclePlayer {
+    fn name(&self) -> String {
+        "oracle".to_string()
+    }
+
+    fn peeks(&self) -> bool {
+        true
+    }
+
+    fn play(&mut self, game: &Game, revealed: Option<Shape>) -> Shape {
+        revealed
+            .and_then(|s| game.shape_for(s, Outcome::Win))
+            .unwrap_or(Shape(0))
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MatchResult {
+    pub players: (String, String),
+    pub scores: (u64, u64),
+    pub wins: (usize, usize),
+    pub draws: usize,
+}
+
+// Rounds are scored for both sides with the same rules as the strategy guide.
+// When only one player peeks, the other one chooses first.
+pub fn play_match(game: &Game, a: &mut dyn Player, b: &mut dyn Player, rounds: usize) -> MatchResult {
+    a.reset();
+    b.reset();
+    let mut result = MatchResult { players: (a.name(), b.name()), ..Default::default() };
+
+    for _ in 0..rounds {
+        let (shape_a, shape_b) = if a.peeks() && !b.peeks() {
+            let shape_b = b.play(game, None);
+            (a.play(game, Some(shape_b)), shape_b)
+        } else {
+            let shape_a = a.play(game, None);
+            (shape_a, b.play(game, b.peeks().then_some(shape_a)))
+        };
+        a.observe(shape_b);
+        b.observe(shape_a);
+
+        result.scores.0 += game.shape_score(shape_a) + game.round_score(shape_b, shape_a);
+        result.scores.1 += game.shape_score(shape_b) + game.round_score(shape_a, shape_b);
+        match game.outcome(shape_b, shape_a) {
+            Outcome::Win => result.wins.0 += 1,
+            Outcome::Loose => result.wins.1 += 1,
+            Outcome::Draw => result.draws += 1,
+        }
+    }
+
+    result
+}
+
+// Every pair of players, once
+pub fn round_robin(game: &Game, players: &mut [Box<dyn Player>], rounds: usize) -> Vec<MatchResult> {
+    let mut results = Vec::new();
+    for i in 0..players.len() {
+        for j in i + 1..players.len() {
+            let (left, right) = players.split_at_mut(j);
+            results.push(play_match(game, left[i].as_mut(), right[0].as_mut(), rounds));
+        }
+    }
+    results
+}
@@ -0,0 +1,145 @@
+use std::{env, fs};
+use std::error::Error;
+use std::process::exit;
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+fn parse_data(data: &str) -> Vec<i64> {
+    data.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().unwrap())
+        .collect()
+}
+
+// Circular list of the original indices, split in blocks of ~sqrt(n) entries so that
+// a move costs O(sqrt(n)) instead of shifting the whole list.
+struct MixingList {
+    blocks: Vec<Vec<usize>>,
+    block_of: Vec<usize>,
+    block_size: usize,
+    len: usize,
+}
+
+impl MixingList {
+    fn new(len: usize) -> Self {
+        let mut list = MixingList {
+            blocks: vec![],
+            block_of: vec![0; len],
+            block_size: ((len as f64).sqrt() as usize).max(1),
+            len,
+        };
+        list.rebuild((0..len).collect());
+        list
+    }
+
+    fn rebuild(&mut self, order: Vec<usize>) {
+        self.blocks = order.chunks(self.block_size).map(|chunk| chunk.to_vec()).collect();
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &element in block {
+                self.block_of[element] = b;
+            }
+        }
+    }
+
+    fn order(&self) -> Vec<usize> {
+        self.blocks.concat()
+    }
+
+    fn remove(&mut self, element: usize) -> usize {
+        let b = self.block_of[element];
+        let offset = self.blocks[b].iter().position(|&e| e == element).unwrap();
+        self.blocks[b].remove(offset);
+
+        self.blocks[..b].iter().map(|block| block.len()).sum::<usize>() + offset
+    }
+
+    fn insert(&mut self, mut position: usize, element: usize) {
+        let mut b = 0;
+        while b < self.blocks.len() - 1 && position > self.blocks[b].len() {
+            position -= self.blocks[b].len();
+            b += 1;
+        }
+        self.blocks[b].insert(position, element);
+        self.block_of[element] = b;
+
+        if self.blocks[b].len() > 2 * self.block_size {
+            self.rebuild(self.order());
+        }
+    }
+
+    fn move_by(&mut self, element: usize, offset: i64) {
+        if self.len < 2 {
+            return;
+        }
+        let position = self.remove(element);
+        let new_position = (position as i64 + offset).rem_euclid(self.len as i64 - 1);
+        self.insert(new_position as usize, element);
+    }
+}
+
+fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    let mut list = MixingList::new(numbers.len());
+
+    for _ in 0..rounds {
+        for (i, &n) in numbers.iter().enumerate() {
+            list.move_by(i, n);
+        }
+    }
+
+    list.order().into_iter().map(|i| numbers[i]).collect()
+}
+
+fn grove_coordinates(mixed: &[i64]) -> i64 {
+    let zero = mixed.iter().position(|&n| n == 0).expect("No zero in the file");
+    [1000, 2000, 3000].iter()
+        .map(|offset| mixed[(zero + offset) % mixed.len()])
+        .sum()
+}
+
+fn solve_part_1(numbers: &[i64]) -> i64 {
+    grove_coordinates(&mix(numbers, 1))
+}
+
+fn solve_part_2(numbers: &[i64]) -> i64 {
+    let numbers = numbers.iter().map(|n| n * DECRYPTION_KEY).collect::<Vec<_>>();
+    grove_coordinates(&mix(&numbers, 10))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Invalid arguments count");
+        exit(1);
+    }
+    let data =
+        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
+
+    let numbers = parse_data(&data);
+
+    println!("part 1: {}", solve_part_1(&numbers));
+    println!("part 2: {}", solve_part_2(&numbers));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_data, solve_part_1, solve_part_2};
+
+    #[test]
+    fn sample() {
+        let numbers = parse_data("1
+2
+-3
+3
+-2
+0
+4
+");
+
+        assert_eq!(solve_part_1(&numbers), 3);
+        assert_eq!(solve_part_2(&numbers), 1623178306);
+    }
+}
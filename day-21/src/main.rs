@@ -0,0 +1,269 @@
+use std::{env, fs};
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::exit;
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    fn from_string(string: &str) -> Self {
+        match string {
+            "+" => Operator::Add,
+            "-" => Operator::Sub,
+            "*" => Operator::Mul,
+            "/" => Operator::Div,
+            _ => panic!("Unknown operator {}", string)
+        }
+    }
+
+    fn apply(&self, a: i64, b: i64) -> i64 {
+        match self {
+            Operator::Add => a + b,
+            Operator::Sub => a - b,
+            Operator::Mul => a * b,
+            Operator::Div => a / b,
+        }
+    }
+
+    // Solves `x op b = result` for x
+    fn solve_left(&self, result: i64, b: i64) -> i64 {
+        match self {
+            Operator::Add => result - b,
+            Operator::Sub => result + b,
+            Operator::Mul => result / b,
+            Operator::Div => result * b,
+        }
+    }
+
+    // Solves `a op x = result` for x
+    fn solve_right(&self, result: i64, a: i64) -> i64 {
+        match self {
+            Operator::Add => result - a,
+            Operator::Sub => a - result,
+            Operator::Mul => result / a,
+            Operator::Div => a / result,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Value(i64),
+    Name(String),
+}
+
+impl Operand {
+    fn from_string(string: &str) -> Self {
+        match string.parse() {
+            Ok(v) => Operand::Value(v),
+            Err(_) => Operand::Name(string.to_string()),
+        }
+    }
+}
+
+// Either a single operand or a binary operation, e.g. `5`, `pppw + sjmn` or `old * 11`.
+// Names are resolved by the caller, so it is not tied to the monkeys of this day.
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Operand(Operand),
+    Operation(Operand, Operator, Operand),
+}
+
+impl Expression {
+    fn from_string(string: &str) -> Self {
+        let parts = string.split_ascii_whitespace().collect::<Vec<_>>();
+        match parts.len() {
+            1 => Expression::Operand(Operand::from_string(parts[0])),
+            3 => Expression::Operation(
+                Operand::from_string(parts[0]),
+                Operator::from_string(parts[1]),
+                Operand::from_string(parts[2]),
+            ),
+            _ => panic!("Invalid expression {}", string)
+        }
+    }
+
+    fn evaluate(&self, resolve: &mut dyn FnMut(&str) -> i64) -> i64 {
+        let mut operand = |operand: &Operand| match operand {
+            Operand::Value(v) => *v,
+            Operand::Name(name) => resolve(name),
+        };
+
+        match self {
+            Expression::Operand(a) => operand(a),
+            Expression::Operation(a, op, b) => {
+                let a = operand(a);
+                op.apply(a, operand(b))
+            }
+        }
+    }
+}
+
+// Monkeys jobs reference each other by name, forming a DAG rooted at `root`
+struct Monkeys {
+    jobs: HashMap<String, Expression>,
+}
+
+impl Monkeys {
+    fn parse_data(data: &str) -> Self {
+        let jobs = data.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (name, job) = line.split_once(':').unwrap();
+                (name.trim().to_string(), Expression::from_string(job))
+            })
+            .collect();
+
+        Monkeys { jobs }
+    }
+
+    fn evaluate(&self, name: &str, cache: &mut HashMap<String, i64>) -> i64 {
+        if let Some(v) = cache.get(name) {
+            return *v;
+        }
+
+        let job = self.jobs.get(name).unwrap_or_else(|| panic!("Unknown monkey {}", name));
+        let v = job.evaluate(&mut |n| self.evaluate(n, cache));
+        cache.insert(name.to_string(), v);
+        v
+    }
+
+    fn evaluate_operand(&self, operand: &Operand, cache: &mut HashMap<String, i64>) -> i64 {
+        match operand {
+            Operand::Value(v) => *v,
+            Operand::Name(name) => self.evaluate(name, cache),
+        }
+    }
+
+    fn depends_on(&self, operand: &Operand, target: &str, cache: &mut HashMap<String, bool>) -> bool {
+        let name = match operand {
+            Operand::Value(_) => return false,
+            Operand::Name(name) => name,
+        };
+        if name == target {
+            return true;
+        }
+        if let Some(d) = cache.get(name) {
+            return *d;
+        }
+
+        let d = match &self.jobs[name] {
+            Expression::Operand(a) => self.depends_on(a, target, cache),
+            Expression::Operation(a, _, b) => {
+                self.depends_on(a, target, cache) || self.depends_on(b, target, cache)
+            }
+        };
+        cache.insert(name.clone(), d);
+        d
+    }
+
+    fn solve_part_1(&self) -> i64 {
+        self.evaluate(ROOT, &mut HashMap::new())
+    }
+
+    // Walks down from root to humn, inverting each operation on the way
+    fn solve_part_2(&self) -> i64 {
+        let mut values = HashMap::new();
+        let mut dependencies = HashMap::new();
+
+        let (mut current, mut target) = match &self.jobs[ROOT] {
+            Expression::Operation(a, _, b) => {
+                match (self.depends_on(a, HUMAN, &mut dependencies), self.depends_on(b, HUMAN, &mut dependencies)) {
+                    (true, false) => (a.clone(), self.evaluate_operand(b, &mut values)),
+                    (false, true) => (b.clone(), self.evaluate_operand(a, &mut values)),
+                    _ => panic!("{} must appear on exactly one side of {}", HUMAN, ROOT)
+                }
+            }
+            Expression::Operand(_) => panic!("{} must be an operation", ROOT)
+        };
+
+        loop {
+            let name = match &current {
+                Operand::Name(name) if name != HUMAN => name,
+                _ => break,
+            };
+
+            match &self.jobs[name] {
+                Expression::Operand(a) => current = a.clone(),
+                Expression::Operation(a, op, b) => {
+                    match (self.depends_on(a, HUMAN, &mut dependencies), self.depends_on(b, HUMAN, &mut dependencies)) {
+                        (true, false) => {
+                            target = op.solve_left(target, self.evaluate_operand(b, &mut values));
+                            current = a.clone();
+                        }
+                        (false, true) => {
+                            target = op.solve_right(target, self.evaluate_operand(a, &mut values));
+                            current = b.clone();
+                        }
+                        _ => panic!("{} must appear on exactly one side of {}", HUMAN, name)
+                    }
+                }
+            }
+        }
+
+        target
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Invalid arguments count");
+        exit(1);
+    }
+    let data =
+        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
+
+    let monkeys = Monkeys::parse_data(&data);
+
+    println!("part 1: {}", monkeys.solve_part_1());
+    println!("part 2: {}", monkeys.solve_part_2());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expression, Monkeys};
+
+    #[test]
+    fn sample() {
+        let monkeys = Monkeys::parse_data("root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32
+");
+
+        assert_eq!(monkeys.solve_part_1(), 152);
+        assert_eq!(monkeys.solve_part_2(), 301);
+    }
+
+    #[test]
+    fn operation() {
+        let expression = Expression::from_string("old * old");
+
+        assert_eq!(expression.evaluate(&mut |_| 7), 49);
+        assert_eq!(Expression::from_string("old + 6").evaluate(&mut |_| 7), 13);
+    }
+}
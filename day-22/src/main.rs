@@ -0,0 +1,391 @@
+use std::{env, fs};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::process::exit;
+
+type Position = (usize, usize);
+type Vector = [i32; 3];
+
+const ALL_FACINGS: [Facing; 4] = [Facing::Right, Facing::Down, Facing::Left, Facing::Up];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Facing {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+impl Facing {
+    fn turn_right(&self) -> Self {
+        match self {
+            Facing::Right => Facing::Down,
+            Facing::Down => Facing::Left,
+            Facing::Left => Facing::Up,
+            Facing::Up => Facing::Right,
+        }
+    }
+
+    fn turn_left(&self) -> Self {
+        match self {
+            Facing::Right => Facing::Up,
+            Facing::Down => Facing::Right,
+            Facing::Left => Facing::Down,
+            Facing::Up => Facing::Left,
+        }
+    }
+
+    fn reverse(&self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    fn delta(&self) -> (i64, i64) {
+        match self {
+            Facing::Right => (1, 0),
+            Facing::Down => (0, 1),
+            Facing::Left => (-1, 0),
+            Facing::Up => (0, -1),
+        }
+    }
+
+    fn score(&self) -> usize {
+        match self {
+            Facing::Right => 0,
+            Facing::Down => 1,
+            Facing::Left => 2,
+            Facing::Up => 3,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Instruction {
+    Forward(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+fn parse_path(line: &str) -> Vec<Instruction> {
+    let mut path = Vec::new();
+    let mut n = None;
+    for c in line.trim().chars() {
+        if let Some(d) = c.to_digit(10) {
+            n = Some(n.unwrap_or(0) * 10 + d as usize);
+            continue;
+        }
+        if let Some(n) = n.take() {
+            path.push(Instruction::Forward(n));
+        }
+        match c {
+            'L' => path.push(Instruction::TurnLeft),
+            'R' => path.push(Instruction::TurnRight),
+            _ => panic!("Unknown instruction {}", c)
+        }
+    }
+    if let Some(n) = n {
+        path.push(Instruction::Forward(n));
+    }
+
+    path
+}
+
+struct Board {
+    tiles: Vec<Vec<u8>>,
+    width: usize,
+    height: usize,
+    path: Vec<Instruction>,
+}
+
+impl Board {
+    fn parse_data(data: &str) -> Self {
+        let lines = data.lines().map(|line| line.trim_end_matches('\r')).collect::<Vec<_>>();
+        let separator = lines.iter().position(|line| line.trim().is_empty()).expect("Missing path instructions");
+
+        let width = lines[..separator].iter().map(|line| line.len()).max().unwrap_or(0);
+        let tiles = lines[..separator].iter().map(|line| {
+            let mut row = line.as_bytes().to_vec();
+            row.resize(width, b' ');
+            row
+        }).collect::<Vec<_>>();
+
+        Board {
+            height: tiles.len(),
+            width,
+            tiles,
+            path: parse_path(lines.get(separator + 1).expect("Missing path instructions")),
+        }
+    }
+
+    fn tile(&self, x: i64, y: i64) -> u8 {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            b' '
+        } else {
+            self.tiles[y as usize][x as usize]
+        }
+    }
+
+    fn start(&self) -> Position {
+        (self.tiles[0].iter().position(|&t| t == b'.').unwrap(), 0)
+    }
+
+    // Walks backwards until the opposite border of the board
+    fn wrap_flat(&self, position: Position, facing: Facing) -> (Position, Facing) {
+        let (dx, dy) = facing.reverse().delta();
+        let (mut x, mut y) = (position.0 as i64, position.1 as i64);
+        while self.tile(x + dx, y + dy) != b' ' {
+            x += dx;
+            y += dy;
+        }
+
+        ((x as usize, y as usize), facing)
+    }
+
+    fn walk(&self, wrap: &dyn Fn(Position, Facing) -> (Position, Facing)) -> usize {
+        let mut position = self.start();
+        let mut facing = Facing::Right;
+
+        for instruction in &self.path {
+            match instruction {
+                Instruction::Forward(n) => {
+                    for _ in 0..*n {
+                        let (dx, dy) = facing.delta();
+                        let (x, y) = (position.0 as i64 + dx, position.1 as i64 + dy);
+                        let (next_position, next_facing) = if self.tile(x, y) == b' ' {
+                            wrap(position, facing)
+                        } else {
+                            ((x as usize, y as usize), facing)
+                        };
+
+                        if self.tiles[next_position.1][next_position.0] == b'#' {
+                            break;
+                        }
+                        position = next_position;
+                        facing = next_facing;
+                    }
+                }
+                Instruction::TurnLeft => facing = facing.turn_left(),
+                Instruction::TurnRight => facing = facing.turn_right(),
+            }
+        }
+
+        1000 * (position.1 + 1) + 4 * (position.0 + 1) + facing.score()
+    }
+
+    fn solve_part_1(&self) -> usize {
+        self.walk(&|position, facing| self.wrap_flat(position, facing))
+    }
+
+    fn solve_part_2(&self) -> usize {
+        let cube = Cube::fold(self);
+        self.walk(&|position, facing| cube.wrap(position, facing))
+    }
+}
+
+fn neg(v: Vector) -> Vector {
+    [-v[0], -v[1], -v[2]]
+}
+
+// A face of the net, with its x (right), y (down) and outward axes once folded in 3D
+#[derive(Debug, Copy, Clone)]
+struct Face {
+    x: usize,
+    y: usize,
+    right: Vector,
+    down: Vector,
+    normal: Vector,
+}
+
+impl Face {
+    fn direction(&self, facing: Facing) -> Vector {
+        match facing {
+            Facing::Right => self.right,
+            Facing::Down => self.down,
+            Facing::Left => neg(self.right),
+            Facing::Up => neg(self.down),
+        }
+    }
+
+    // Orientation of the face glued on the given side, when folding the net
+    fn neighbour(&self, facing: Facing) -> Face {
+        let (dx, dy) = facing.delta();
+        let (x, y) = ((self.x as i64 + dx) as usize, (self.y as i64 + dy) as usize);
+        match facing {
+            Facing::Right => Face { x, y, right: neg(self.normal), down: self.down, normal: self.right },
+            Facing::Left => Face { x, y, right: self.normal, down: self.down, normal: neg(self.right) },
+            Facing::Down => Face { x, y, right: self.right, down: neg(self.normal), normal: self.down },
+            Facing::Up => Face { x, y, right: self.right, down: self.normal, normal: neg(self.down) },
+        }
+    }
+}
+
+struct Cube {
+    size: usize,
+    faces: Vec<Face>,
+}
+
+impl Cube {
+    fn fold(board: &Board) -> Self {
+        let tiles = board.tiles.iter().flatten().filter(|&&t| t != b' ').count();
+        let size = ((tiles / 6) as f64).sqrt() as usize;
+        assert_eq!(size * size * 6, tiles, "The board is not a cube net");
+
+        let is_face = |x: i64, y: i64| board.tile(x * size as i64, y * size as i64) != b' ';
+        let (width, height) = ((board.width / size) as i64, (board.height / size) as i64);
+        let first = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)))
+            .find(|&(x, y)| is_face(x, y))
+            .unwrap();
+
+        let mut faces = vec![Face {
+            x: first.0 as usize,
+            y: first.1 as usize,
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+            normal: [0, 0, 1],
+        }];
+        let mut queue = VecDeque::from([faces[0]]);
+        while let Some(face) = queue.pop_front() {
+            for facing in ALL_FACINGS {
+                let (dx, dy) = facing.delta();
+                let (x, y) = (face.x as i64 + dx, face.y as i64 + dy);
+                if x < 0 || y < 0 || !is_face(x, y) || faces.iter().any(|f| (f.x as i64, f.y as i64) == (x, y)) {
+                    continue;
+                }
+                let neighbour = face.neighbour(facing);
+                faces.push(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+        assert_eq!(faces.len(), 6, "The board is not a cube net");
+
+        Cube { size, faces }
+    }
+
+    fn face_at(&self, position: Position) -> &Face {
+        self.faces.iter()
+            .find(|f| (f.x, f.y) == (position.0 / self.size, position.1 / self.size))
+            .unwrap()
+    }
+
+    fn wrap(&self, position: Position, facing: Facing) -> (Position, Facing) {
+        let face = self.face_at(position);
+        let (x, y) = (position.0 % self.size, position.1 % self.size);
+
+        // The face we move onto is the one facing where we were heading, and we now head "down" the cube
+        let exit = face.direction(facing);
+        let target = self.faces.iter().find(|f| f.normal == exit).unwrap();
+        let heading = neg(face.normal);
+        let new_facing = ALL_FACINGS.into_iter().find(|&f| target.direction(f) == heading).unwrap();
+
+        // Offset along the shared edge, flipped if both faces run it in opposite directions
+        let (along, offset) = match facing {
+            Facing::Right | Facing::Left => (face.down, y),
+            Facing::Down | Facing::Up => (face.right, x),
+        };
+        let target_along = match new_facing {
+            Facing::Right | Facing::Left => target.down,
+            Facing::Down | Facing::Up => target.right,
+        };
+        let offset = if along == target_along { offset } else { self.size - 1 - offset };
+
+        let (x, y) = match new_facing {
+            Facing::Right => (0, offset),
+            Facing::Left => (self.size - 1, offset),
+            Facing::Down => (offset, 0),
+            Facing::Up => (offset, self.size - 1),
+        };
+
+        ((target.x * self.size + x, target.y * self.size + y), new_facing)
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Invalid arguments count");
+        exit(1);
+    }
+    let data =
+        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
+
+    let board = Board::parse_data(&data);
+
+    println!("part 1: {}", board.solve_part_1());
+    println!("part 2: {}", board.solve_part_2());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Cube, ALL_FACINGS};
+
+    const SAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5
+";
+
+    // Leaving the cube through any edge and coming back must land on the starting tile
+    fn assert_wrap_round_trip(board: &Board) {
+        let cube = Cube::fold(board);
+
+        for y in 0..board.height {
+            for x in 0..board.width {
+                if board.tiles[y][x] == b' ' {
+                    continue;
+                }
+                for facing in ALL_FACINGS {
+                    let (dx, dy) = facing.delta();
+                    if board.tile(x as i64 + dx, y as i64 + dy) != b' ' {
+                        continue;
+                    }
+                    let (position, new_facing) = cube.wrap((x, y), facing);
+                    assert_eq!(cube.wrap(position, new_facing.reverse()), ((x, y), facing.reverse()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sample() {
+        let board = Board::parse_data(SAMPLE);
+
+        assert_eq!(board.solve_part_1(), 6032);
+        assert_eq!(board.solve_part_2(), 5031);
+        assert_wrap_round_trip(&board);
+    }
+
+    #[test]
+    fn other_net() {
+        let board = Board::parse_data("  ....
+  ....
+  ..
+  ..
+....
+....
+..
+..
+
+1
+");
+
+        assert_wrap_round_trip(&board);
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing path instructions")]
+    fn missing_path() {
+        Board::parse_data("...#\n.#..\n\n");
+    }
+}
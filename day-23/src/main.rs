@@ -0,0 +1,191 @@
+use std::{env, fs};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::process::exit;
+
+type Position = (i64, i64);
+
+const NEIGHBOURS: [Position; 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+// Direction to move to, and the three positions that must be free to propose it
+const PROPOSALS: [(Position, [Position; 3]); 4] = [
+    ((0, -1), [(-1, -1), (0, -1), (1, -1)]),
+    ((0, 1), [(-1, 1), (0, 1), (1, 1)]),
+    ((-1, 0), [(-1, -1), (-1, 0), (-1, 1)]),
+    ((1, 0), [(1, -1), (1, 0), (1, 1)]),
+];
+
+#[derive(Clone)]
+struct Grove {
+    elves: HashSet<Position>,
+    first_proposal: usize,
+}
+
+impl Grove {
+    fn parse(data: &str) -> Grove {
+        let mut elves = HashSet::new();
+        for (y, line) in data.lines().enumerate() {
+            for (x, c) in line.trim().chars().enumerate() {
+                if c == '#' {
+                    elves.insert((x as i64, y as i64));
+                }
+            }
+        }
+
+        Grove {
+            elves,
+            first_proposal: 0,
+        }
+    }
+
+    fn is_free(&self, elf: Position, offset: Position) -> bool {
+        !self.elves.contains(&(elf.0 + offset.0, elf.1 + offset.1))
+    }
+
+    fn propose(&self, elf: Position) -> Option<Position> {
+        if NEIGHBOURS.iter().all(|&n| self.is_free(elf, n)) {
+            return None;
+        }
+
+        (0..PROPOSALS.len())
+            .map(|i| PROPOSALS[(self.first_proposal + i) % PROPOSALS.len()])
+            .find(|(_, checks)| checks.iter().all(|&c| self.is_free(elf, c)))
+            .map(|(direction, _)| (elf.0 + direction.0, elf.1 + direction.1))
+    }
+
+    // Plays a round, and returns whether any elf moved
+    fn round(&mut self) -> bool {
+        let proposals = self.elves.iter()
+            .filter_map(|&elf| self.propose(elf).map(|target| (elf, target)))
+            .collect::<Vec<_>>();
+
+        let mut targets: HashMap<Position, usize> = HashMap::new();
+        for (_, target) in &proposals {
+            *targets.entry(*target).or_default() += 1;
+        }
+
+        let mut moved = false;
+        for (elf, target) in proposals {
+            if targets[&target] == 1 {
+                self.elves.remove(&elf);
+                self.elves.insert(target);
+                moved = true;
+            }
+        }
+        self.first_proposal = (self.first_proposal + 1) % PROPOSALS.len();
+
+        moved
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        let x_min = self.elves.iter().map(|e| e.0).min().unwrap_or(0);
+        let x_max = self.elves.iter().map(|e| e.0).max().unwrap_or(0);
+        let y_min = self.elves.iter().map(|e| e.1).min().unwrap_or(0);
+        let y_max = self.elves.iter().map(|e| e.1).max().unwrap_or(0);
+
+        ((x_min, y_min), (x_max, y_max))
+    }
+
+    fn empty_tiles(&self) -> usize {
+        let ((x_min, y_min), (x_max, y_max)) = self.bounds();
+        ((x_max + 1 - x_min) * (y_max + 1 - y_min)) as usize - self.elves.len()
+    }
+
+    fn solve_part_1(&self) -> usize {
+        let mut grove = self.clone();
+        for _ in 0..10 {
+            grove.round();
+        }
+
+        grove.empty_tiles()
+    }
+
+    fn solve_part_2(&self) -> usize {
+        let mut grove = self.clone();
+        let mut round = 1;
+        while grove.round() {
+            round += 1;
+        }
+
+        round
+    }
+}
+
+impl fmt::Display for Grove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ((x_min, y_min), (x_max, y_max)) = self.bounds();
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                write!(f, "{}", if self.elves.contains(&(x, y)) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Invalid arguments count");
+        exit(1);
+    }
+    let data =
+        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
+
+    let grove = Grove::parse(&data);
+
+    println!("part 1: {}", grove.solve_part_1());
+    println!("part 2: {}", grove.solve_part_2());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grove;
+
+    #[test]
+    fn sample() {
+        let data = "....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+        let grove = Grove::parse(data);
+
+        assert_eq!(grove.to_string(), data);
+        assert_eq!(grove.solve_part_1(), 110);
+        assert_eq!(grove.solve_part_2(), 20);
+    }
+
+    #[test]
+    fn render() {
+        let mut grove = Grove::parse("....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+");
+        assert!(grove.round());
+
+        assert_eq!(grove.to_string(), ".....#...
+...#...#.
+.#..#.#..
+.....#..#
+..#.#.##.
+#..#.#...
+#.#.#.##.
+.........
+..#..#...
+");
+    }
+}
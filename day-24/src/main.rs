@@ -0,0 +1,164 @@
+use std::{env, fs};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::process::exit;
+
+type Position = (usize, usize);
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+struct Valley {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+    // Blizzards come back to their initial layout every lcm(inner width, inner height) minutes
+    period: usize,
+    start_location: Position,
+    target_location: Position,
+}
+
+fn parse_data(data: &str) -> Valley {
+    let lines = data.lines().map(|line| line.trim()).filter(|line| !line.is_empty()).collect::<Vec<_>>();
+    let width = lines[0].len();
+    let height = lines.len();
+
+    Valley {
+        grid: lines.iter().flat_map(|line| line.bytes()).collect(),
+        width,
+        height,
+        period: lcm(width - 2, height - 2),
+        start_location: (lines[0].find('.').unwrap(), 0),
+        target_location: (lines[height - 1].find('.').unwrap(), height - 1),
+    }
+}
+
+impl Valley {
+    fn tile(&self, x: usize, y: usize) -> u8 {
+        self.grid[y * self.width + x]
+    }
+
+    fn is_free(&self, (x, y): Position, time: usize) -> bool {
+        if self.tile(x, y) == b'#' {
+            return false;
+        }
+        if y == 0 || y == self.height - 1 {
+            return true;
+        }
+
+        // Look where each kind of blizzard that could be here now started from
+        let (w, h) = (self.width - 2, self.height - 2);
+        let (ix, iy) = (x - 1, y - 1);
+        let (tw, th) = (time % w, time % h);
+
+        self.tile((ix + w - tw) % w + 1, y) != b'>'
+            && self.tile((ix + tw) % w + 1, y) != b'<'
+            && self.tile(x, (iy + h - th) % h + 1) != b'v'
+            && self.tile(x, (iy + th) % h + 1) != b'^'
+    }
+
+    fn neighbours(&self, (x, y): Position) -> Vec<Position> {
+        let mut neighbours = vec![(x, y)];
+        if x > 0 {
+            neighbours.push((x - 1, y));
+        }
+        if x < self.width - 1 {
+            neighbours.push((x + 1, y));
+        }
+        if y > 0 {
+            neighbours.push((x, y - 1));
+        }
+        if y < self.height - 1 {
+            neighbours.push((x, y + 1));
+        }
+        neighbours
+    }
+
+    // BFS over (position, time mod period), returns the arrival time if the target can be reached
+    fn crossing_time(&self, start_location: Position, target_location: Position, start_time: usize) -> Option<usize> {
+        let mut visited = vec![false; self.width * self.height * self.period];
+        let mut queue = VecDeque::from([(start_location, start_time)]);
+
+        while let Some((position, time)) = queue.pop_front() {
+            if position == target_location {
+                return Some(time);
+            }
+
+            for next in self.neighbours(position) {
+                let next_time = time + 1;
+                let state = (next_time % self.period) * self.width * self.height + next.1 * self.width + next.0;
+                if !visited[state] && self.is_free(next, next_time) {
+                    visited[state] = true;
+                    queue.push_back((next, next_time));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn solve_part_1(valley: &Valley) -> Option<usize> {
+    valley.crossing_time(valley.start_location, valley.target_location, 0)
+}
+
+fn solve_part_2(valley: &Valley) -> Option<usize> {
+    let there = valley.crossing_time(valley.start_location, valley.target_location, 0)?;
+    let back = valley.crossing_time(valley.target_location, valley.start_location, there)?;
+    valley.crossing_time(valley.start_location, valley.target_location, back)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Invalid arguments count");
+        exit(1);
+    }
+    let data =
+        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
+
+    let valley = parse_data(&data);
+
+    for (part, time) in [(1, solve_part_1(&valley)), (2, solve_part_2(&valley))] {
+        match time {
+            Some(time) => println!("part {}: {}", part, time),
+            None => println!("part {}: no path", part),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_data, solve_part_1, solve_part_2};
+
+    #[test]
+    fn sample() {
+        let valley = parse_data("#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+");
+
+        assert_eq!(valley.period, 12);
+        assert_eq!(solve_part_1(&valley), Some(18));
+        assert_eq!(solve_part_2(&valley), Some(54));
+    }
+
+    #[test]
+    fn no_path() {
+        let valley = parse_data("#.#\n#v#\n#.#");
+
+        assert_eq!(solve_part_1(&valley), None);
+        assert_eq!(solve_part_2(&valley), None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::{env, fs};
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+use std::process::exit;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+enum SnafuError {
+    Empty,
+    InvalidDigit(char),
+    Overflow,
+}
+
+impl fmt::Display for SnafuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnafuError::Empty => write!(f, "Empty SNAFU number"),
+            SnafuError::InvalidDigit(c) => write!(f, "Invalid SNAFU digit {}", c),
+            SnafuError::Overflow => write!(f, "SNAFU number too large"),
+        }
+    }
+}
+
+impl Error for SnafuError {}
+
+// Balanced base 5 number, digits in -2..=2 stored from the least significant one,
+// without leading zeros (zero is an empty list)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Snafu {
+    digits: Vec<i8>,
+}
+
+impl Snafu {
+    fn normalized(mut digits: Vec<i8>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Snafu { digits }
+    }
+
+    fn to_i128_checked(&self) -> Option<i128> {
+        self.digits.iter().rev().try_fold(0i128, |acc, &d| {
+            acc.checked_mul(5)?.checked_add(d as i128)
+        })
+    }
+}
+
+impl FromStr for Snafu {
+    type Err = SnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(SnafuError::Empty);
+        }
+
+        let digits = s.chars().rev().map(|c| match c {
+            '2' => Ok(2),
+            '1' => Ok(1),
+            '0' => Ok(0),
+            '-' => Ok(-1),
+            '=' => Ok(-2),
+            _ => Err(SnafuError::InvalidDigit(c)),
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Snafu::normalized(digits))
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+        for d in self.digits.iter().rev() {
+            let c = match d {
+                2 => '2',
+                1 => '1',
+                0 => '0',
+                -1 => '-',
+                _ => '=',
+            };
+            write!(f, "{}", c)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<i128> for Snafu {
+    fn from(mut n: i128) -> Self {
+        let mut digits = Vec::new();
+        while n != 0 {
+            let r = n.rem_euclid(5);
+            let (d, carry) = if r > 2 { (r - 5, 1) } else { (r, 0) };
+            digits.push(d as i8);
+            n = n.div_euclid(5) + carry;
+        }
+
+        Snafu { digits }
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(n: i64) -> Self {
+        Snafu::from(n as i128)
+    }
+}
+
+impl TryFrom<&Snafu> for i128 {
+    type Error = SnafuError;
+
+    fn try_from(snafu: &Snafu) -> Result<Self, Self::Error> {
+        snafu.to_i128_checked().ok_or(SnafuError::Overflow)
+    }
+}
+
+impl TryFrom<&Snafu> for i64 {
+    type Error = SnafuError;
+
+    fn try_from(snafu: &Snafu) -> Result<Self, Self::Error> {
+        let n = i128::try_from(snafu)?;
+        i64::try_from(n).map_err(|_| SnafuError::Overflow)
+    }
+}
+
+// Digit by digit addition with carry, never going through a binary integer
+impl Add for &Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: &Snafu) -> Snafu {
+        let mut digits = Vec::with_capacity(self.digits.len().max(other.digits.len()) + 1);
+        let mut carry = 0;
+        for i in 0..self.digits.len().max(other.digits.len()) {
+            let sum = self.digits.get(i).unwrap_or(&0) + other.digits.get(i).unwrap_or(&0) + carry;
+            let d = (sum + 2).rem_euclid(5) - 2;
+            carry = (sum - d) / 5;
+            digits.push(d);
+        }
+        if carry != 0 {
+            digits.push(carry);
+        }
+
+        Snafu::normalized(digits)
+    }
+}
+
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: Snafu) -> Snafu {
+        &self + &other
+    }
+}
+
+impl<'a> Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, n| &acc + n)
+    }
+}
+
+fn parse_data(data: &str) -> Result<Vec<Snafu>, SnafuError> {
+    data.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse())
+        .collect()
+}
+
+fn solve_part_1(requirements: &[Snafu]) -> Snafu {
+    requirements.iter().sum()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Invalid arguments count");
+        exit(1);
+    }
+    let data =
+        fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
+
+    let requirements = parse_data(&data)?;
+
+    println!("part 1: {}", solve_part_1(&requirements));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_data, solve_part_1, Snafu, SnafuError};
+
+    #[test]
+    fn conversions() {
+        let table: [(i64, &str); 12] = [
+            (0, "0"),
+            (1, "1"),
+            (2, "2"),
+            (3, "1="),
+            (4, "1-"),
+            (5, "10"),
+            (8, "2="),
+            (20, "1-0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+            (-3, "-2"),
+        ];
+
+        for (n, s) in table {
+            let snafu = s.parse::<Snafu>().unwrap();
+            assert_eq!(snafu, Snafu::from(n));
+            assert_eq!(snafu.to_string(), s);
+            assert_eq!(i64::try_from(&snafu), Ok(n));
+        }
+
+        assert_eq!("12a".parse::<Snafu>(), Err(SnafuError::InvalidDigit('a')));
+        assert_eq!(i64::try_from(&Snafu::from(i128::MAX)), Err(SnafuError::Overflow));
+        assert_eq!(i128::try_from(&Snafu::from(i128::MIN + 2)), Ok(i128::MIN + 2));
+    }
+
+    #[test]
+    fn sample() {
+        let requirements = parse_data("1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122
+").unwrap();
+
+        let sum = solve_part_1(&requirements);
+        assert_eq!(sum.to_string(), "2=-1=0");
+        assert_eq!(i64::try_from(&sum), Ok(4890));
+    }
+}
@@ -3,22 +3,101 @@ mod rucksack;
 use std::error::Error;
 use std::{env, fs};
 use std::process::exit;
-use crate::rucksack::{parse_data, solve_part_1, solve_part_2};
+use crate::rucksack::{audit, audit_table, parse_data, solve_part_1, solve_part_2};
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
+    if args.len() != 2 && args.len() != 3 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
     let file_content =
         fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
 
-    let ref rucksacks = parse_data(file_content);
+    match args.get(2).map(|mode| mode.as_str()) {
+        None => {}
+        Some("audit") => {
+            print!("{}", audit_table(&audit(&file_content)));
+            return Ok(());
+        }
+        Some(mode) => {
+            eprintln!("Unknown mode {}, expected audit", mode);
+            exit(1);
+        }
+    }
+
+    let rucksacks = parse_data(file_content);
 
-    println!("part 1: {}", solve_part_1(rucksacks));
-    println!("part 2: {}", solve_part_2(rucksacks));
+    println!("part 1: {}", solve_part_1(&rucksacks)?);
+    println!("part 2: {}", solve_part_2(&rucksacks)?);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rucksack::{audit, audit_table, badges, parse_data, solve_part_1, solve_part_2, AuditIssue, BadgeError, ItemSet, NoDuplicateError};
+
+    const SAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn sample() {
+        let rucksacks = parse_data(SAMPLE.to_string());
+
+        assert_eq!(solve_part_1(&rucksacks), Ok(157));
+        assert_eq!(solve_part_2(&rucksacks), Ok(70));
+        assert_eq!(badges(&rucksacks, 3), Ok(vec!['r', 'Z']));
+    }
+
+    #[test]
+    fn item_sets() {
+        let set = ItemSet::from_items("aZbZ");
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.items().collect::<String>(), "abZ");
+        assert!(set.intersection(ItemSet::from_items("cY")).is_empty());
+    }
+
+    #[test]
+    fn badge_errors() {
+        let rucksacks = parse_data(SAMPLE.to_string());
+
+        assert_eq!(badges(&rucksacks, 0), Err(BadgeError::InvalidGroupSize));
+        assert_eq!(badges(&rucksacks, 4), Err(BadgeError::IncompleteGroup { group: 1, rucksacks: 2 }));
+        assert_eq!(badges(&rucksacks[..2], 2), Err(BadgeError::AmbiguousBadge { group: 0, items: "frsFM".to_string() }));
+        assert_eq!(badges(&rucksacks, 6), Err(BadgeError::NoBadge { group: 0 }));
+    }
+
+    #[test]
+    fn no_duplicate() {
+        let rucksacks = parse_data("vJrwpWtwJgWrhcsFMMfFFhFp\nabcd\n".to_string());
+
+        assert_eq!(solve_part_1(&rucksacks), Err(NoDuplicateError { rucksack: 2 }));
+    }
+
+    #[test]
+    fn audit_report() {
+        let entries = audit("vJrwpWtwJgWrhcsFMMfFFhFp
+abcab
+ab1ab2
+abcd
+");
+
+        assert_eq!(entries[0].shared.items().collect::<String>(), "p");
+        assert!(entries[0].issues.is_empty());
+        assert_eq!(entries[1].issues, vec![AuditIssue::OddLength(5), AuditIssue::MultipleDuplicates]);
+        assert_eq!(entries[2].issues, vec![AuditIssue::InvalidItems("12".to_string()), AuditIssue::MultipleDuplicates]);
+        assert_eq!(entries[3].issues, vec![AuditIssue::NoDuplicate]);
+
+        let table = audit_table(&entries);
+        assert!(table.starts_with("line | rucksack                 | shared | issues\n"));
+        assert!(table.contains("4    | abcd                     |        | no duplicate\n"));
+    }
+}
@@ -1,49 +1,245 @@
+use std::error::Error;
+use std::fmt;
+
+// Set of items as a 52 bits mask, bit i being the item of priority i + 1
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub fn from_items(items: &str) -> Self {
+        let (set, invalid) = ItemSet::from_letters(items);
+        if let Some(c) = invalid.chars().next() {
+            panic!("Invalid item {}", c);
+        }
+        set
+    }
+
+    // Set of the letters, and the other characters which are not items
+    pub fn from_letters(items: &str) -> (Self, String) {
+        let mut invalid = String::new();
+        let mask = items.chars().fold(0, |mask, c| match char_priority(c) {
+            Some(priority) => mask | 1 << (priority - 1),
+            None => {
+                invalid.push(c);
+                mask
+            }
+        });
+        (ItemSet(mask), invalid)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    // Items by increasing priority
+    pub fn items(self) -> impl Iterator<Item = char> {
+        (0..52).filter(move |bit| self.0 & 1 << bit != 0).map(priority_char)
+    }
+}
+
 pub struct Rucksack {
-    pub all_items: String,
-    pub compartment_a: Vec<char>,
-    pub compartment_b: Vec<char>,
+    pub all_items: ItemSet,
+    pub compartment_a: ItemSet,
+    pub compartment_b: ItemSet,
 }
 
 impl Rucksack {
-    fn find_duplicated_item(&self) -> char {
-        for c in &self.compartment_a {
-            if self.compartment_b.contains(c) {
-                return *c;
-            }
-        }
-        panic!("Duplicated item not found");
+    pub fn shared_items(&self) -> ItemSet {
+        self.compartment_a.intersection(self.compartment_b)
+    }
+
+    fn find_duplicated_item(&self) -> Option<char> {
+        self.shared_items().items().next()
     }
 }
 
-fn char_priority(c: char) -> u64 {
-    if c.is_lowercase() {
-        c as u64 - 'a' as u64 + 1
-    } else {
-        c as u64 - 'A' as u64 + 27
+#[derive(Debug, PartialEq)]
+pub enum AuditIssue {
+    OddLength(usize),
+    InvalidItems(String),
+    NoDuplicate,
+    MultipleDuplicates,
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditIssue::OddLength(length) => write!(f, "odd length {}", length),
+            AuditIssue::InvalidItems(items) => write!(f, "invalid items {:?}", items),
+            AuditIssue::NoDuplicate => write!(f, "no duplicate"),
+            AuditIssue::MultipleDuplicates => write!(f, "multiple duplicates"),
+        }
     }
 }
 
-pub fn solve_part_1(rucksacks: &Vec<Rucksack>) -> u64 {
-    rucksacks.iter().map(|rucksack| {
-        let c = rucksack.find_duplicated_item();
-        char_priority(c)
+#[derive(Debug)]
+pub struct AuditEntry {
+    pub line: usize,
+    pub items: String,
+    pub shared: ItemSet,
+    pub issues: Vec<AuditIssue>,
+}
+
+// Checks every line without panicking, the compartments of odd-length lines
+// being split with the extra item in the second one
+pub fn audit(data: &str) -> Vec<AuditEntry> {
+    data.lines().enumerate().map(|(i, line)| {
+        let items = line.trim().chars().collect::<Vec<_>>();
+        let (compartment_a, compartment_b) = items.split_at(items.len() / 2);
+        let (compartment_a, invalid_a) = ItemSet::from_letters(&compartment_a.iter().collect::<String>());
+        let (compartment_b, invalid_b) = ItemSet::from_letters(&compartment_b.iter().collect::<String>());
+        let shared = compartment_a.intersection(compartment_b);
+
+        let mut issues = Vec::new();
+        if items.len() % 2 == 1 {
+            issues.push(AuditIssue::OddLength(items.len()));
+        }
+        if !invalid_a.is_empty() || !invalid_b.is_empty() {
+            issues.push(AuditIssue::InvalidItems(invalid_a + &invalid_b));
+        }
+        match shared.len() {
+            0 => issues.push(AuditIssue::NoDuplicate),
+            1 => {}
+            _ => issues.push(AuditIssue::MultipleDuplicates),
+        }
+
+        AuditEntry {
+            line: i + 1,
+            items: items.into_iter().collect(),
+            shared,
+            issues,
+        }
     })
-        .sum()
+        .collect()
 }
 
-pub fn solve_part_2(rucksacks: &Vec<Rucksack>) -> u64 {
-    let mut result = 0;
+pub fn audit_table(entries: &[AuditEntry]) -> String {
+    let rows = entries.iter().map(|e| [
+        e.line.to_string(),
+        e.items.clone(),
+        e.shared.items().collect(),
+        e.issues.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", "),
+    ]).collect::<Vec<_>>();
+    let header = ["line", "rucksack", "shared", "issues"].map(|h| h.to_string());
 
-    for i in (0..rucksacks.len()).step_by(3) {
-        for c in rucksacks[i].all_items.chars() {
-            if rucksacks[i + 1].all_items.contains(c) && rucksacks[i + 2].all_items.contains(c) {
-                result += char_priority(c);
-                break;
-            }
+    let mut widths = header.clone().map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells = row.iter().zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        table.push_str(cells.join(" | ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BadgeError {
+    InvalidGroupSize,
+    IncompleteGroup { group: usize, rucksacks: usize },
+    NoBadge { group: usize },
+    AmbiguousBadge { group: usize, items: String },
+}
+
+impl fmt::Display for BadgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BadgeError::InvalidGroupSize => write!(f, "Groups need at least one rucksack"),
+            BadgeError::IncompleteGroup { group, rucksacks } => write!(f, "Group {} only has {} rucksacks", group + 1, rucksacks),
+            BadgeError::NoBadge { group } => write!(f, "Group {} has no common item", group + 1),
+            BadgeError::AmbiguousBadge { group, items } => write!(f, "Group {} has several common items: {}", group + 1, items),
         }
     }
+}
 
-    result
+impl Error for BadgeError {}
+
+// Rucksack, counted from 1, whose compartments share no item
+#[derive(Debug, PartialEq)]
+pub struct NoDuplicateError {
+    pub rucksack: usize,
+}
+
+impl fmt::Display for NoDuplicateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rucksack {} has no item in both compartments", self.rucksack)
+    }
+}
+
+impl Error for NoDuplicateError {}
+
+fn char_priority(c: char) -> Option<u64> {
+    if c.is_ascii_lowercase() {
+        Some(c as u64 - 'a' as u64 + 1)
+    } else if c.is_ascii_uppercase() {
+        Some(c as u64 - 'A' as u64 + 27)
+    } else {
+        None
+    }
+}
+
+fn priority_char(bit: u64) -> char {
+    if bit < 26 {
+        (b'a' + bit as u8) as char
+    } else {
+        (b'A' + (bit - 26) as u8) as char
+    }
+}
+
+// The single item shared by each group of consecutive rucksacks
+pub fn badges(rucksacks: &[Rucksack], group_size: usize) -> Result<Vec<char>, BadgeError> {
+    if group_size == 0 {
+        return Err(BadgeError::InvalidGroupSize);
+    }
+
+    if !rucksacks.len().is_multiple_of(group_size) {
+        return Err(BadgeError::IncompleteGroup {
+            group: rucksacks.len() / group_size,
+            rucksacks: rucksacks.len() % group_size,
+        });
+    }
+
+    rucksacks.chunks(group_size).enumerate().map(|(group, rucksacks)| {
+        let common = rucksacks.iter()
+            .map(|r| r.all_items)
+            .reduce(ItemSet::intersection)
+            .unwrap();
+        if common.is_empty() {
+            Err(BadgeError::NoBadge { group })
+        } else if common.len() > 1 {
+            Err(BadgeError::AmbiguousBadge { group, items: common.items().collect() })
+        } else {
+            Ok(common.items().next().unwrap())
+        }
+    })
+        .collect()
+}
+
+pub fn solve_part_1(rucksacks: &[Rucksack]) -> Result<u64, NoDuplicateError> {
+    rucksacks.iter().enumerate().map(|(i, rucksack)| {
+        let c = rucksack.find_duplicated_item().ok_or(NoDuplicateError { rucksack: i + 1 })?;
+        Ok(char_priority(c).unwrap())
+    })
+        .sum()
+}
+
+pub fn solve_part_2(rucksacks: &[Rucksack]) -> Result<u64, BadgeError> {
+    Ok(badges(rucksacks, 3)?.into_iter().map(|c| char_priority(c).unwrap()).sum())
 }
 
 pub fn parse_data(data: String) -> Vec<Rucksack> {
@@ -51,10 +247,10 @@ pub fn parse_data(data: String) -> Vec<Rucksack> {
         let line = line.trim();
         let half_size = line.len() / 2;
         Rucksack {
-            all_items: line.to_string(),
-            compartment_a: line[..half_size].chars().collect(),
-            compartment_b: line[half_size..].chars().collect(),
+            all_items: ItemSet::from_items(line),
+            compartment_a: ItemSet::from_items(&line[..half_size]),
+            compartment_b: ItemSet::from_items(&line[half_size..]),
         }
     })
         .collect()
-}
\ No newline at end of file
+}
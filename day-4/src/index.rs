@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use crate::ElfRange;
+
+// Elf of a line of the list, 0 for the first assignment and 1 for the second
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ElfId {
+    pub pair: usize,
+    pub elf: usize,
+}
+
+// Centered interval tree: ranges containing the center stay in the node, sorted both by
+// start and by end, and the others go down the side they are entirely on
+struct Node {
+    center: usize,
+    by_start: Vec<(ElfRange, ElfId)>,
+    by_end: Vec<(ElfRange, ElfId)>,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+impl Node {
+    fn build(ranges: Vec<(ElfRange, ElfId)>) -> Option<Box<Node>> {
+        if ranges.is_empty() {
+            return None;
+        }
+
+        let mut bounds = ranges.iter().flat_map(|(r, _)| [r.start, r.end]).collect::<Vec<_>>();
+        bounds.sort_unstable();
+        let center = bounds[bounds.len() / 2];
+
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        let mut here = Vec::new();
+        for (range, id) in ranges {
+            if range.end < center {
+                left.push((range, id));
+            } else if range.start > center {
+                right.push((range, id));
+            } else {
+                here.push((range, id));
+            }
+        }
+
+        let mut by_start = here.clone();
+        by_start.sort_by_key(|(r, id)| (r.start, *id));
+        let mut by_end = here;
+        by_end.sort_by_key(|(r, id)| (std::cmp::Reverse(r.end), *id));
+
+        Some(Box::new(Node {
+            center,
+            by_start,
+            by_end,
+            left: Node::build(left),
+            right: Node::build(right),
+        }))
+    }
+
+    fn stab(&self, section: usize, result: &mut Vec<ElfId>) {
+        if section < self.center {
+            result.extend(self.by_start.iter().take_while(|(r, _)| r.start <= section).map(|(_, id)| *id));
+            if let Some(left) = &self.left {
+                left.stab(section, result);
+            }
+        } else {
+            result.extend(self.by_end.iter().take_while(|(r, _)| r.end >= section).map(|(_, id)| *id));
+            if section > self.center {
+                if let Some(right) = &self.right {
+                    right.stab(section, result);
+                }
+            }
+        }
+    }
+}
+
+// Index over every assignment of the list, not only the two of a line
+pub struct AssignmentIndex {
+    ranges: Vec<(ElfRange, ElfId)>,
+    tree: Option<Box<Node>>,
+}
+
+impl AssignmentIndex {
+    pub fn new(elves: &[(ElfRange, ElfRange)]) -> Self {
+        let ranges = elves.iter().enumerate()
+            .flat_map(|(pair, (a, b))| [(*a, ElfId { pair, elf: 0 }), (*b, ElfId { pair, elf: 1 })])
+            .collect::<Vec<_>>();
+
+        AssignmentIndex {
+            tree: Node::build(ranges.clone()),
+            ranges,
+        }
+    }
+
+    // Elves assigned to the section, in O(log n + k)
+    pub fn overlapping(&self, section: usize) -> Vec<ElfId> {
+        let mut result = Vec::new();
+        if let Some(tree) = &self.tree {
+            tree.stab(section, &mut result);
+        }
+        result.sort();
+        result
+    }
+
+    // (container, contained) pairs. Sweeping by increasing start then decreasing end, the
+    // containers of a range are the ones already seen which end after it.
+    pub fn containments(&self) -> Vec<(ElfId, ElfId)> {
+        let mut sorted = self.ranges.clone();
+        sorted.sort_by_key(|(r, id)| (r.start, std::cmp::Reverse(r.end), *id));
+
+        let mut seen: BTreeMap<usize, Vec<ElfId>> = BTreeMap::new();
+        let mut result = Vec::new();
+        for (range, id) in sorted {
+            for containers in seen.range(range.end..).map(|(_, ids)| ids) {
+                result.extend(containers.iter().map(|container| (*container, id)));
+            }
+            seen.entry(range.end).or_default().push(id);
+        }
+        result
+    }
+
+    // Highest number of elves assigned to a same section, and the first such section
+    pub fn max_concurrent(&self) -> (usize, usize) {
+        // Ends are inclusive, so on a same section they come after the starts
+        let mut events = self.ranges.iter()
+            .flat_map(|(r, _)| [(r.start, false), (r.end, true)])
+            .collect::<Vec<(usize, bool)>>();
+        events.sort();
+
+        let mut current = 0;
+        let mut best = (0, 0);
+        for (section, is_end) in events {
+            if is_end {
+                current -= 1;
+            } else {
+                current += 1;
+                if current > best.0 {
+                    best = (current, section);
+                }
+            }
+        }
+        best
+    }
+}
@@ -1,35 +1,193 @@
+mod index;
+
 use std::error::Error;
+use std::fmt;
 use std::{env, fs};
 use std::process::exit;
+use crate::index::AssignmentIndex;
 
+// Inclusive range of sections
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct ElfRange {
     start: usize,
     end: usize,
 }
 
+impl ElfRange {
+    // Saturates for the range of every section, one more than usize can hold
+    fn len(&self) -> usize {
+        (self.end - self.start).saturating_add(1)
+    }
+
+    fn contains(&self, section: usize) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    fn contains_range(&self, other: &ElfRange) -> bool {
+        self.contains(other.start) && self.contains(other.end)
+    }
+
+    fn overlaps(&self, other: &ElfRange) -> bool {
+        !(self.end < other.start || other.end < self.start)
+    }
+
+    fn intersection(&self, other: &ElfRange) -> Option<ElfRange> {
+        if self.overlaps(other) {
+            Some(ElfRange { start: self.start.max(other.start), end: self.end.min(other.end) })
+        } else {
+            None
+        }
+    }
+
+    // Single range covering both, if they overlap or touch
+    fn union(&self, other: &ElfRange) -> Option<ElfRange> {
+        if self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1) {
+            Some(ElfRange { start: self.start.min(other.start), end: self.end.max(other.end) })
+        } else {
+            None
+        }
+    }
+
+    // Sections of self not in other, in zero, one or two pieces
+    fn difference(&self, other: &ElfRange) -> Vec<ElfRange> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut pieces = Vec::new();
+        if let Some(end) = other.start.checked_sub(1).filter(|&end| end >= self.start) {
+            pieces.push(ElfRange { start: self.start, end });
+        }
+        if let Some(start) = other.end.checked_add(1).filter(|&start| start <= self.end) {
+            pieces.push(ElfRange { start, end: self.end });
+        }
+        pieces
+    }
+}
+
+impl fmt::Display for ElfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// Sorted disjoint spans covering the same sections
+fn normalize(ranges: &[ElfRange]) -> Vec<ElfRange> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort();
+
+    let mut spans: Vec<ElfRange> = Vec::new();
+    for range in sorted {
+        match spans.last_mut().and_then(|last| last.union(&range).map(|union| (last, union))) {
+            Some((last, union)) => *last = union,
+            None => spans.push(range),
+        }
+    }
+    spans
+}
+
+// Sections between the first and the last assigned ones which nobody has
+fn unassigned(ranges: &[ElfRange]) -> Vec<ElfRange> {
+    let spans = normalize(ranges);
+    let (first, last) = match (spans.first(), spans.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return vec![],
+    };
+
+    // Spans are sorted, so each one can only cut the remaining pieces after the previous one
+    let mut gaps = vec![ElfRange { start: first.start, end: last.end }];
+    for span in &spans {
+        if let Some(rest) = gaps.pop() {
+            gaps.extend(rest.difference(span));
+        }
+    }
+    gaps
+}
+
 fn fully_overlap(a: &ElfRange, b: &ElfRange) -> bool {
-    (a.start <= b.start && a.end >= b.end) || (b.start <= a.start && b.end >= a.end)
+    a.contains_range(b) || b.contains_range(a)
 }
 
 fn partially_overlap(a: &ElfRange, b: &ElfRange) -> bool {
-    !(a.end < b.start || b.end < a.start)
+    a.overlaps(b)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Reversed {
+    Reject,
+    Normalize,
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    MissingPair,
+    TooManyRanges(usize),
+    InvalidSection(String),
+    ReversedRange(usize, usize),
+}
+
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ParseErrorKind::MissingPair => write!(f, "expected two comma separated assignments"),
+            ParseErrorKind::TooManyRanges(count) => write!(f, "expected two assignments, found {}", count),
+            ParseErrorKind::InvalidSection(section) => write!(f, "invalid section {:?}", section),
+            ParseErrorKind::ReversedRange(start, end) => write!(f, "reversed range {}-{}", start, end),
+        }
+    }
 }
 
-fn parse_data(data: String) -> Vec<(ElfRange, ElfRange)> {
-    data.lines().map(|line| {
-        let parts : Vec<&str> = line.trim().split(&[',', '-']).collect();
-        (ElfRange {
-            start: parts[0].parse().unwrap(),
-            end: parts[1].parse().unwrap(),
-        },
-         ElfRange {
-             start: parts[2].parse().unwrap(),
-             end: parts[3].parse().unwrap(),
-         })
-    }).collect()
+impl Error for ParseError {}
+
+// Either `start-end` or a single section, with any spacing around the numbers
+fn parse_range(range: &str, reversed: Reversed) -> Result<ElfRange, ParseErrorKind> {
+    let section = |s: &str| {
+        let s = s.trim();
+        s.parse::<usize>().map_err(|_| ParseErrorKind::InvalidSection(s.to_string()))
+    };
+
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (section(start)?, section(end)?),
+        None => {
+            let s = section(range)?;
+            (s, s)
+        }
+    };
+
+    match (start <= end, reversed) {
+        (true, _) => Ok(ElfRange { start, end }),
+        (false, Reversed::Normalize) => Ok(ElfRange { start: end, end: start }),
+        (false, Reversed::Reject) => Err(ParseErrorKind::ReversedRange(start, end)),
+    }
 }
 
-fn solve_part_1(elves: &Vec<(ElfRange, ElfRange)>) -> usize {
+// Blank lines are skipped, any other invalid line is an error naming it
+fn parse_data(data: String, reversed: Reversed) -> Result<Vec<(ElfRange, ElfRange)>, ParseError> {
+    data.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let error = |kind| ParseError { line: i + 1, kind };
+            let parts = line.split(',').collect::<Vec<_>>();
+            match parts[..] {
+                [a, b] => Ok((
+                    parse_range(a, reversed).map_err(error)?,
+                    parse_range(b, reversed).map_err(error)?,
+                )),
+                [_] => Err(error(ParseErrorKind::MissingPair)),
+                _ => Err(error(ParseErrorKind::TooManyRanges(parts.len()))),
+            }
+        })
+        .collect()
+}
+
+fn solve_part_1(elves: &[(ElfRange, ElfRange)]) -> usize {
     let mut result = 0;
     for (a, b) in elves {
         if fully_overlap(a, b) {
@@ -39,7 +197,7 @@ fn solve_part_1(elves: &Vec<(ElfRange, ElfRange)>) -> usize {
     result
 }
 
-fn solve_part_2(elves: &Vec<(ElfRange, ElfRange)>) -> usize {
+fn solve_part_2(elves: &[(ElfRange, ElfRange)]) -> usize {
     let mut result = 0;
     for (a, b) in elves {
         if partially_overlap(a, b) {
@@ -49,20 +207,187 @@ fn solve_part_2(elves: &Vec<(ElfRange, ElfRange)>) -> usize {
     result
 }
 
+fn report(elves: &[(ElfRange, ElfRange)]) -> String {
+    let mut report = String::new();
+    for (a, b) in elves {
+        let overlap = a.intersection(b).map_or(0, |i| i.len());
+        report.push_str(&format!("{},{}: overlap {}\n", a, b, overlap));
+    }
+
+    let all = elves.iter().flat_map(|(a, b)| [*a, *b]).collect::<Vec<_>>();
+    let gaps = unassigned(&all).iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    report.push_str(&format!("unassigned: {}\n", gaps.join(", ")));
+    report
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // Reversed ranges are errors unless --normalize is given
+    let reversed = match args.iter().position(|arg| arg == "--normalize") {
+        Some(i) => {
+            args.remove(i);
+            Reversed::Normalize
+        }
+        None => Reversed::Reject,
+    };
 
-    if args.len() != 2 {
+    if args.len() < 2 || args.len() > 4 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
     let file_content =
         fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
 
-    let ref elf_ranges = parse_data(file_content);
+    let elf_ranges = &parse_data(file_content, reversed)?;
 
-    println!("part 1: {}", solve_part_1(elf_ranges));
-    println!("part 2: {}", solve_part_2(elf_ranges));
+    match args.get(2).map(|mode| mode.as_str()) {
+        None => {
+            println!("part 1: {}", solve_part_1(elf_ranges));
+            println!("part 2: {}", solve_part_2(elf_ranges));
+        }
+        Some("report") => print!("{}", report(elf_ranges)),
+        Some("section") => {
+            let section = args.get(3).ok_or("Missing section number")?.parse()?;
+            for id in AssignmentIndex::new(elf_ranges).overlapping(section) {
+                println!("line {} elf {}", id.pair + 1, id.elf + 1);
+            }
+        }
+        Some("containments") => {
+            for (a, b) in AssignmentIndex::new(elf_ranges).containments() {
+                println!("line {} elf {} contains line {} elf {}", a.pair + 1, a.elf + 1, b.pair + 1, b.elf + 1);
+            }
+        }
+        Some("concurrency") => {
+            let (count, section) = AssignmentIndex::new(elf_ranges).max_concurrent();
+            println!("{} elves on section {}", count, section);
+        }
+        Some(mode) => {
+            eprintln!("Unknown mode {}, expected report, section, containments or concurrency", mode);
+            exit(1);
+        }
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{normalize, parse_data, report, solve_part_1, solve_part_2, unassigned, ElfRange, ParseError, ParseErrorKind, Reversed};
+    use crate::index::{AssignmentIndex, ElfId};
+
+    fn range(start: usize, end: usize) -> ElfRange {
+        ElfRange { start, end }
+    }
+
+    #[test]
+    fn sample() {
+        let elves = parse_data("2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+".to_string(), Reversed::Reject).unwrap();
+
+        assert_eq!(solve_part_1(&elves), 2);
+        assert_eq!(solve_part_2(&elves), 4);
+        assert!(report(&elves).starts_with("2-4,6-8: overlap 0\n2-3,4-5: overlap 0\n5-7,7-9: overlap 1\n"));
+    }
+
+    #[test]
+    fn interval_algebra() {
+        assert_eq!(range(2, 6).len(), 5);
+        assert_eq!(range(2, 6).intersection(&range(4, 8)), Some(range(4, 6)));
+        assert_eq!(range(2, 3).intersection(&range(4, 8)), None);
+        assert_eq!(range(2, 3).union(&range(4, 8)), Some(range(2, 8)));
+        assert_eq!(range(2, 3).union(&range(5, 8)), None);
+        assert_eq!(range(2, 8).difference(&range(4, 5)), vec![range(2, 3), range(6, 8)]);
+        assert_eq!(range(4, 5).difference(&range(2, 8)), vec![]);
+        assert_eq!(range(2, 3).difference(&range(5, 8)), vec![range(2, 3)]);
+
+        let all = range(0, usize::MAX);
+        assert_eq!(all.len(), usize::MAX);
+        assert_eq!(all.union(&range(1, 2)), Some(all));
+        assert_eq!(range(usize::MAX, usize::MAX).union(&range(0, usize::MAX - 1)), Some(all));
+        assert_eq!(all.difference(&range(0, 1)), vec![range(2, usize::MAX)]);
+        assert_eq!(all.difference(&range(5, usize::MAX)), vec![range(0, 4)]);
+        assert_eq!(report(&[(all, range(1, 2))]), format!("0-{},1-2: overlap 2\nunassigned: \n", usize::MAX));
+
+        let ranges = [range(10, 12), range(2, 4), range(3, 5), range(6, 6), range(20, 21)];
+        assert_eq!(normalize(&ranges), vec![range(2, 6), range(10, 12), range(20, 21)]);
+        assert_eq!(unassigned(&ranges), vec![range(7, 9), range(13, 19)]);
+        assert_eq!(unassigned(&[]), vec![]);
+    }
+
+    #[test]
+    fn assignment_index() {
+        let elves = parse_data("2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+".to_string(), Reversed::Reject).unwrap();
+        let index = AssignmentIndex::new(&elves);
+        let id = |pair, elf| ElfId { pair, elf };
+
+        // Checked against a brute force scan of every section
+        for section in 0..12 {
+            let mut expected = Vec::new();
+            for (pair, (a, b)) in elves.iter().enumerate() {
+                for (elf, r) in [a, b].into_iter().enumerate() {
+                    if r.contains(section) {
+                        expected.push(id(pair, elf));
+                    }
+                }
+            }
+            assert_eq!(index.overlapping(section), expected);
+        }
+
+        let containments = index.containments();
+        assert!(containments.contains(&(id(3, 0), id(3, 1))));
+        assert!(containments.contains(&(id(4, 1), id(4, 0))));
+        assert!(containments.contains(&(id(3, 0), id(0, 0))));
+        assert!(!containments.contains(&(id(0, 0), id(3, 0))));
+        let ranges = elves.iter().flat_map(|(a, b)| [*a, *b]).collect::<Vec<_>>();
+        let mut brute_force = 0;
+        for (i, a) in ranges.iter().enumerate() {
+            for (j, b) in ranges.iter().enumerate() {
+                // Identical ranges are only reported once
+                if i != j && a.contains_range(b) && (a != b || i < j) {
+                    brute_force += 1;
+                }
+            }
+        }
+        assert_eq!(containments.len(), brute_force);
+
+        assert_eq!(index.max_concurrent(), (8, 6));
+
+        let last = usize::MAX;
+        let index = AssignmentIndex::new(&[(range(0, last), range(1, 2)), (range(last, last), range(2, 2))]);
+        assert_eq!(index.max_concurrent(), (3, 2));
+        assert_eq!(index.overlapping(last), vec![id(0, 0), id(1, 0)]);
+    }
+
+    #[test]
+    fn parsing() {
+        let elves = parse_data(" 2 - 4 , 6-8\n\n5,3-3\n".to_string(), Reversed::Reject).unwrap();
+        assert_eq!(elves, vec![
+            (ElfRange { start: 2, end: 4 }, ElfRange { start: 6, end: 8 }),
+            (ElfRange { start: 5, end: 5 }, ElfRange { start: 3, end: 3 }),
+        ]);
+
+        let error = |line, kind| Err(ParseError { line, kind });
+        assert_eq!(parse_data("2-4,6-8\n7-3,1-2".to_string(), Reversed::Reject),
+                   error(2, ParseErrorKind::ReversedRange(7, 3)));
+        assert_eq!(parse_data("7-3,1-2".to_string(), Reversed::Normalize).unwrap()[0].0,
+                   ElfRange { start: 3, end: 7 });
+        assert_eq!(parse_data("2-4".to_string(), Reversed::Reject), error(1, ParseErrorKind::MissingPair));
+        assert_eq!(parse_data("1,2,3".to_string(), Reversed::Reject), error(1, ParseErrorKind::TooManyRanges(3)));
+        assert_eq!(parse_data("1-x,2".to_string(), Reversed::Reject),
+                   error(1, ParseErrorKind::InvalidSection("x".to_string())));
+        assert_eq!(parse_data("1--2,2".to_string(), Reversed::Reject),
+                   error(1, ParseErrorKind::InvalidSection("-2".to_string())));
+    }
+}
@@ -1,99 +1,310 @@
+mod mover;
+
 use std::{env, fs};
 use std::error::Error;
+use std::fmt;
 use std::process::exit;
+use crate::mover::{rearrange, CrateMover, CrateMover9000, CrateMover9001, MoveError, MoveErrorKind, Replay, Validation};
 
+#[derive(Debug, Clone, PartialEq)]
 struct Cargo {
-    stacks: [Vec<char>; 9],
+    // Crates of each stack, from bottom to top
+    stacks: Vec<Vec<char>>,
 }
 
 impl Cargo {
-    fn new() -> Self {
-        Cargo {
-            stacks: [
-                vec!['T', 'D', 'W', 'Z', 'V', 'P'],
-                vec!['L', 'S', 'W', 'V', 'F', 'J', 'D'],
-                vec!['Z', 'M', 'L', 'S', 'V', 'T', 'B', 'H'],
-                vec!['R', 'S', 'J'],
-                vec!['C', 'Z', 'B', 'G', 'F', 'M', 'L', 'W'],
-                vec!['Q', 'W', 'V', 'H', 'Z', 'R', 'G', 'B'],
-                vec!['V', 'J', 'P', 'C', 'B', 'D', 'N'],
-                vec!['P', 'T', 'B', 'Q'],
-                vec!['H', 'G', 'Z', 'R', 'C'],
-            ],
+    // Drawing of the stacks, one `[X]` box per crate and the stack numbers as last line
+    fn parse(drawing: &str) -> Result<Self, Box<dyn Error>> {
+        let mut lines = drawing.lines().rev();
+        let footer = lines.next().ok_or("Missing stacks drawing")?;
+
+        let numbers = footer.split_whitespace().collect::<Vec<_>>();
+        for (i, number) in numbers.iter().enumerate() {
+            if number.parse::<usize>().ok() != Some(i + 1) {
+                return Err(format!("Invalid stack number {}, expected {}", number, i + 1).into());
+            }
+        }
+
+        let mut stacks = vec![Vec::new(); numbers.len()];
+        for (row, line) in lines.enumerate() {
+            let line = line.chars().collect::<Vec<_>>();
+            for (column, cell) in line.chunks(4).enumerate() {
+                match cell {
+                    [' ', ' ', ' '] | [' ', ' ', ' ', ' '] => {}
+                    ['[', c, ']'] | ['[', c, ']', ' '] if column < stacks.len() => {
+                        if stacks[column].len() != row {
+                            return Err(format!("Crate {} of stack {} is floating", c, column + 1).into());
+                        }
+                        stacks[column].push(*c);
+                    }
+                    _ => return Err(format!("Invalid crate {:?} in stack {}", cell.iter().collect::<String>(), column + 1).into()),
+                }
+            }
         }
+
+        Ok(Cargo { stacks })
+    }
+
+    fn top_crates(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.last()).collect()
     }
 }
 
-fn parse_data(data: String) -> Vec<(usize, usize, usize)> {
-    data.lines().map(|line| {
-        let parts = line[5..].split(" from ").collect::<Vec<_>>();
-        let count = parts[0].parse().unwrap();
-        let parts = parts[1].trim().split(" to ").collect::<Vec<_>>();
-        (count, parts[0].parse().unwrap(), parts[1].trim().parse().unwrap())
-    }).collect()
+// Same drawing as the input, which `Cargo::parse` reads back
+impl fmt::Display for Cargo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let cells = self.stacks.iter()
+                .map(|s| s.get(row).map_or("   ".to_string(), |c| format!("[{}]", c)))
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+
+        let numbers = (1..=self.stacks.len()).map(|n| format!("{:^3}", n)).collect::<Vec<_>>();
+        writeln!(f, "{}", numbers.join(" ").trim_end())
+    }
 }
 
-fn solve_part_1(moves: &Vec<(usize, usize, usize)>) -> String {
-    let mut cargo = Cargo::new();
+// Stacks are numbered from 1 as in the input
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Move {
+    line: usize,
+    count: usize,
+    from: usize,
+    to: usize,
+}
 
-    for (count, from, to) in moves {
-        let from = from - 1;
-        let to = to - 1;
-        for _ in 0..*count {
-            if cargo.stacks[from].len() > 0 {
-                let character = cargo.stacks[from].pop().unwrap();
-                cargo.stacks[to].push(character);
+// `move N from A to B` lines, blank ones being skipped
+fn parse_moves(moves: &str, first_line: usize) -> Result<Vec<Move>, MoveError> {
+    moves.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            let parsed = match parts[..] {
+                ["move", count, "from", from, "to", to] => count.parse().ok()
+                    .zip(from.parse().ok())
+                    .zip(to.parse().ok()),
+                _ => None,
+            };
+            match parsed {
+                Some(((count, from), to)) => Ok(Move { line: first_line + i, count, from, to }),
+                None => Err(MoveError { line: first_line + i, kind: MoveErrorKind::InvalidMove(line.to_string()) }),
             }
-        }
-    }
+        })
+        .collect()
+}
 
-    let mut result = String::new();
-    for s in cargo.stacks {
-        if s.len() > 0 {
-            result.push(*s.last().unwrap())
-        }
-    }
-    result
+// The stacks drawing, then the moves after a blank line
+fn parse_data(data: String) -> Result<(Cargo, Vec<Move>), Box<dyn Error>> {
+    let data = data.replace("\r\n", "\n");
+    let (drawing, moves) = data.split_once("\n\n").ok_or("Missing blank line after the stacks drawing")?;
+    Ok((Cargo::parse(drawing)?, parse_moves(moves, drawing.lines().count() + 2)?))
 }
 
-fn solve_part_2(moves: &Vec<(usize, usize, usize)>) -> String {
-    let mut cargo = Cargo::new();
+fn solve_part_1(cargo: &Cargo, moves: &[Move], validation: Validation) -> Result<String, MoveError> {
+    Ok(rearrange(&CrateMover9000, cargo, moves, validation)?.top_crates())
+}
 
-    for (count, from, to) in moves {
-        let from = from - 1;
-        let to = to - 1;
+fn solve_part_2(cargo: &Cargo, moves: &[Move], validation: Validation) -> Result<String, MoveError> {
+    Ok(rearrange(&CrateMover9001, cargo, moves, validation)?.top_crates())
+}
 
-        let from_length = cargo.stacks[from].len();
-        let from_copy = cargo.stacks[from].clone();
-        let crates = &from_copy[from_length - count ..];
-        cargo.stacks[to].extend_from_slice(crates);
-        cargo.stacks[from].truncate(from_length - count);
+fn print_step(replay: &Replay, step: usize) {
+    match replay.step_move(step) {
+        Some(m) => println!("step {}: move {} from {} to {}", step, m.count, m.from, m.to),
+        None => println!("step {}: start", step),
+    }
+    for change in replay.diff(step) {
+        println!("  stack {}: -{:?} +{:?}", change.stack, change.removed.iter().collect::<String>(), change.added.iter().collect::<String>());
     }
+    println!("{}", replay.state(step).unwrap());
+}
 
-    let mut result = String::new();
-    for s in cargo.stacks {
-        if s.len() > 0 {
-            result.push(*s.last().unwrap())
+fn crane_model(model: Option<&String>) -> &'static dyn CrateMover {
+    match model.map(|model| model.as_str()) {
+        Some("9000") => &CrateMover9000,
+        Some("9001") => &CrateMover9001,
+        _ => {
+            eprintln!("Expected the crane model, 9000 or 9001");
+            exit(1);
         }
     }
-    result
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
+    // Moves asking for more crates than the stack holds are errors unless --lenient is given
+    let validation = match args.iter().position(|arg| arg == "--lenient") {
+        Some(i) => {
+            args.remove(i);
+            Validation::Lenient
+        }
+        None => Validation::Strict,
+    };
+
+    if args.len() < 2 || args.len() > 5 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
     let file_content =
         fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
 
-    let ref moves = parse_data(file_content);
+    let (cargo, moves) = parse_data(file_content)?;
 
-
-    println!("part 1: {}", solve_part_1(moves));
-    println!("part 2: {}", solve_part_2(moves));
+    match args.get(2).map(|mode| mode.as_str()) {
+        None => {
+            println!("part 1: {}", solve_part_1(&cargo, &moves, validation)?);
+            println!("part 2: {}", solve_part_2(&cargo, &moves, validation)?);
+        }
+        // replay <9000|9001> [step], every step when none is given
+        Some("replay") => {
+            let mover = crane_model(args.get(3));
+            let replay = Replay::record(mover, &cargo, &moves, validation)?;
+            println!("{}", mover.name());
+            match args.get(4) {
+                Some(step) => {
+                    let step = step.parse()?;
+                    if step > replay.move_count() {
+                        return Err(format!("Step {} is past the last move {}", step, replay.move_count()).into());
+                    }
+                    print_step(&replay, step);
+                }
+                None => (0..=replay.move_count()).for_each(|step| print_step(&replay, step)),
+            }
+        }
+        // show <9000|9001>, the stacks before and after the rearrangement
+        Some("show") => {
+            let mover = crane_model(args.get(3));
+            println!("before:\n{}", cargo);
+            println!("after, with the {}:\n{}", mover.name(), rearrange(mover, &cargo, &moves, validation)?);
+        }
+        Some(mode) => {
+            eprintln!("Unknown mode {}, expected replay or show", mode);
+            exit(1);
+        }
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_data, parse_moves, solve_part_1, solve_part_2, Cargo};
+    use crate::mover::{rearrange, CrateMover9000, CrateMover9001, MoveError, MoveErrorKind, Replay, StackChange, Validation};
+
+    const SAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn sample() {
+        let (cargo, moves) = parse_data(SAMPLE.to_string()).unwrap();
+
+        assert_eq!(cargo.stacks, vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+        assert_eq!(solve_part_1(&cargo, &moves, Validation::Strict).unwrap(), "CMZ");
+        assert_eq!(solve_part_2(&cargo, &moves, Validation::Strict).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn drawing() {
+        let cargo = Cargo::parse("[A]             [B]
+[C]     [D]     [E]     [F]     [G]     [H]     [I]     [J]
+ 1   2   3   4   5   6   7   8   9   10  11  12  13  14  15").unwrap();
+        assert_eq!(cargo.stacks.len(), 15);
+        assert_eq!(cargo.stacks[0], vec!['C', 'A']);
+        assert_eq!(cargo.stacks[4], vec!['E', 'B']);
+        assert!(cargo.stacks[13].is_empty());
+        assert_eq!(cargo.top_crates(), "ADBFGHIJ");
+
+        assert!(Cargo::parse("[A]\n 2").is_err());
+        assert!(Cargo::parse("[A] [B]\n 1").is_err());
+        assert!(Cargo::parse("    [A]\n[B]\n 1   2").is_err());
+        assert!(Cargo::parse("[A]\n[B] [C]\n 1   2").is_ok());
+        assert!(Cargo::parse("[A]\n   \n 1").is_err());
+    }
+
+    #[test]
+    fn replay() {
+        let (cargo, moves) = parse_data(SAMPLE.to_string()).unwrap();
+        let replay = Replay::record(&CrateMover9000, &cargo, &moves, Validation::Strict).unwrap();
+
+        assert_eq!(replay.move_count(), 4);
+        assert_eq!(replay.state(0), Some(&cargo));
+        assert_eq!(replay.state(2).unwrap().stacks, vec![vec![], vec!['M', 'C'], vec!['P', 'D', 'N', 'Z']]);
+        assert_eq!(replay.state(5), None);
+        assert_eq!(replay.diff(2), vec![
+            StackChange { stack: 1, removed: vec!['Z', 'N', 'D'], added: vec![] },
+            StackChange { stack: 3, removed: vec![], added: vec!['D', 'N', 'Z'] },
+        ]);
+        assert!(replay.diff(0).is_empty());
+
+        let replay = Replay::record(&CrateMover9001, &cargo, &moves, Validation::Strict).unwrap();
+        assert_eq!(replay.diff(2)[1], StackChange { stack: 3, removed: vec![], added: vec!['Z', 'N', 'D'] });
+        assert_eq!(replay.state(4).unwrap().top_crates(), "MCD");
+    }
+
+    #[test]
+    fn validation() {
+        let (cargo, moves) = parse_data("[A]
+[B] [C]
+ 1   2
+
+move 1 from 2 to 1
+move 3 from 2 to 1
+move 1 from 3 to 1
+".to_string()).unwrap();
+
+        let missing = MoveError { line: 6, kind: MoveErrorKind::MissingCrates { stack: 2, needed: 3, available: 0 } };
+        assert_eq!(solve_part_1(&cargo, &moves[..2], Validation::Strict), Err(missing));
+        assert_eq!(solve_part_2(&cargo, &moves[..2], Validation::Lenient).unwrap(), "C");
+        assert_eq!(solve_part_1(&cargo, &moves[..2], Validation::Lenient).unwrap(), "C");
+
+        let unknown = MoveError { line: 7, kind: MoveErrorKind::UnknownStack(3) };
+        assert_eq!(solve_part_2(&cargo, &moves, Validation::Lenient), Err(unknown));
+        assert_eq!(
+            MoveError { line: 6, kind: MoveErrorKind::MissingCrates { stack: 2, needed: 3, available: 1 } }.to_string(),
+            "line 6: stack 2 has 1 crates, 2 short of 3",
+        );
+    }
+
+    #[test]
+    fn render() {
+        let (cargo, moves) = parse_data(SAMPLE.to_string()).unwrap();
+        assert_eq!(cargo.to_string(), SAMPLE.split_once("\n\n").unwrap().0.to_string() + "\n");
+
+        let rearranged = rearrange(&CrateMover9000, &cargo, &moves, Validation::Strict).unwrap();
+        assert_eq!(rearranged.to_string(), "        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3
+");
+        assert_eq!(Cargo::parse(rearranged.to_string().trim_end()).unwrap(), rearranged);
+
+        let wide = Cargo { stacks: (0..12).map(|i| vec!['A'; i % 3]).collect() };
+        assert_eq!(Cargo::parse(wide.to_string().trim_end()).unwrap(), wide);
+        assert_eq!(Cargo { stacks: vec![vec![], vec![]] }.to_string(), " 1   2\n");
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let (_, moves) = parse_data("[A]\n 1\n\nmove 1 from 1 to 1\n\n".to_string()).unwrap();
+        assert_eq!(moves.len(), 1);
+
+        let error = parse_data("[A]\n 1\n\nmove 1 from 1 to 1\n\nmove x from 1 to 2\n".to_string()).unwrap_err();
+        assert_eq!(error.to_string(), "line 6: invalid move \"move x from 1 to 2\"");
+        assert_eq!(
+            parse_moves("\nmove 1\n", 4),
+            Err(MoveError { line: 5, kind: MoveErrorKind::InvalidMove("move 1".to_string()) }),
+        );
+    }
+}
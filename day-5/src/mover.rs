@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Cargo, Move};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Validation {
+    Strict,
+    // Moves what the stack holds when it has fewer crates than asked, as the first crane did
+    Lenient,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MoveErrorKind {
+    InvalidMove(String),
+    UnknownStack(usize),
+    MissingCrates { stack: usize, needed: usize, available: usize },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MoveError {
+    pub line: usize,
+    pub kind: MoveErrorKind,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            MoveErrorKind::InvalidMove(line) => write!(f, "invalid move {:?}", line),
+            MoveErrorKind::UnknownStack(stack) => write!(f, "unknown stack {}", stack),
+            MoveErrorKind::MissingCrates { stack, needed, available } =>
+                write!(f, "stack {} has {} crates, {} short of {}", stack, available, needed - available, needed),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+pub trait CrateMover {
+    fn name(&self) -> &str;
+
+    // Stacks are indices here, and the source one holds at least count crates
+    fn move_crates(&self, cargo: &mut Cargo, from: usize, to: usize, count: usize);
+
+    fn apply(&self, cargo: &mut Cargo, m: &Move, validation: Validation) -> Result<(), MoveError> {
+        let error = |kind| MoveError { line: m.line, kind };
+        for stack in [m.from, m.to] {
+            if stack == 0 || stack > cargo.stacks.len() {
+                return Err(error(MoveErrorKind::UnknownStack(stack)));
+            }
+        }
+
+        let available = cargo.stacks[m.from - 1].len();
+        let count = match validation {
+            Validation::Strict if available < m.count => {
+                return Err(error(MoveErrorKind::MissingCrates { stack: m.from, needed: m.count, available }));
+            }
+            Validation::Strict => m.count,
+            Validation::Lenient => m.count.min(available),
+        };
+
+        self.move_crates(cargo, m.from - 1, m.to - 1, count);
+        Ok(())
+    }
+}
+
+// Moves the crates one at a time, so they land in reverse order
+pub struct CrateMover9000;
+
+impl CrateMover for CrateMover9000 {
+    fn name(&self) -> &str {
+        "CrateMover 9000"
+    }
+
+    fn move_crates(&self, cargo: &mut Cargo, from: usize, to: usize, count: usize) {
+        for _ in 0..count {
+            let c = cargo.stacks[from].pop().unwrap();
+            cargo.stacks[to].push(c);
+        }
+    }
+}
+
+// Moves the crates all at once, keeping their order
+pub struct CrateMover9001;
+
+impl CrateMover for CrateMover9001 {
+    fn name(&self) -> &str {
+        "CrateMover 9001"
+    }
+
+    fn move_crates(&self, cargo: &mut Cargo, from: usize, to: usize, count: usize) {
+        let source = &mut cargo.stacks[from];
+        let crates = source.split_off(source.len() - count);
+        cargo.stacks[to].extend(crates);
+    }
+}
+
+pub fn rearrange(mover: &dyn CrateMover, cargo: &Cargo, moves: &[Move], validation: Validation) -> Result<Cargo, MoveError> {
+    let mut cargo = cargo.clone();
+    for m in moves {
+        mover.apply(&mut cargo, m, validation)?;
+    }
+    Ok(cargo)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StackChange {
+    pub stack: usize,
+    pub removed: Vec<char>,
+    pub added: Vec<char>,
+}
+
+// State of the cargo before the first move and after each of them
+pub struct Replay {
+    moves: Vec<Move>,
+    states: Vec<Cargo>,
+}
+
+impl Replay {
+    pub fn record(mover: &dyn CrateMover, cargo: &Cargo, moves: &[Move], validation: Validation) -> Result<Self, MoveError> {
+        let mut states = vec![cargo.clone()];
+        for m in moves {
+            let mut next = states.last().unwrap().clone();
+            mover.apply(&mut next, m, validation)?;
+            states.push(next);
+        }
+
+        Ok(Replay { moves: moves.to_vec(), states })
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.moves.len()
+    }
+
+    // Step 0 is the starting state, step i the state after the i-th move
+    pub fn state(&self, step: usize) -> Option<&Cargo> {
+        self.states.get(step)
+    }
+
+    pub fn step_move(&self, step: usize) -> Option<&Move> {
+        step.checked_sub(1).and_then(|i| self.moves.get(i))
+    }
+
+    // Crates taken from the top of each stack and put on it by the step
+    pub fn diff(&self, step: usize) -> Vec<StackChange> {
+        let (Some(before), Some(after)) = (step.checked_sub(1).and_then(|i| self.state(i)), self.state(step)) else {
+            return Vec::new();
+        };
+
+        before.stacks.iter().zip(&after.stacks).enumerate()
+            .filter(|(_, (b, a))| b != a)
+            .map(|(i, (b, a))| {
+                let common = b.iter().zip(a).take_while(|(x, y)| x == y).count();
+                StackChange { stack: i + 1, removed: b[common..].to_vec(), added: a[common..].to_vec() }
+            })
+            .collect()
+    }
+}
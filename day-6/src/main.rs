@@ -1,86 +1,74 @@
+mod scanner;
+
 use std::{env, fs};
 use std::error::Error;
+use std::io;
 use std::process::exit;
+use crate::scanner::{Marker, MarkerKind, MarkerScanner, SignalScanner};
 
-fn solve_part_1(data: String) -> usize {
-    let mut diff_count = 0;
-    let mut previous = ['0', '0', '0'];
-
-    for i in 0..data.len() {
-        let c = data.chars().nth(i).unwrap();
-        let mut same = usize::MAX;
-        for j in 0..diff_count {
-            if c == previous[j] {
-                same = j;
-            }
-        }
-
-        if same != usize::MAX {
-            for j in same..diff_count - 1 {
-                previous[j - same] = previous[1 + j];
-            }
-            diff_count -= same + 1;
-        }
-
-        diff_count += 1;
-        if diff_count == 4 {
-            return i + 1;
-        }
-        previous[diff_count - 1] = data.chars().nth(i).unwrap();
+// Number of bytes read when the last `window` ones are all different
+fn find_marker(stream: &[u8], window: usize) -> Option<usize> {
+    if window == 0 {
+        return Some(0);
     }
 
-    0
+    let mut scanner = MarkerScanner::new(window);
+    stream.iter().position(|&byte| scanner.push(byte)).map(|i| i + 1)
 }
 
-fn solve_part_2(data: String) -> usize {
-    let mut diff_count = 0;
-    let mut previous = ['0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0'];
-
-    for i in 0..data.len() {
-        let c = data.chars().nth(i).unwrap();
-        let mut same = usize::MAX;
-        for j in 0..diff_count {
-            if c == previous[j] {
-                same = j;
-            }
-        }
-
-        if same != usize::MAX {
-            for j in same..diff_count - 1 {
-                previous[j - same] = previous[1 + j];
-            }
-            diff_count -= same + 1;
-        }
-
-        diff_count += 1;
-        if diff_count == 14 {
-            return i + 1;
-        }
-        previous[diff_count - 1] = data.chars().nth(i).unwrap();
-    }
+fn solve_part_1(data: String) -> usize {
+    find_marker(data.trim().as_bytes(), 4).unwrap_or(0)
+}
 
-    0
+fn solve_part_2(data: String) -> usize {
+    find_marker(data.trim().as_bytes(), 14).unwrap_or(0)
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
+    if args.len() < 2 || args.len() > 3 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
+
+    // Every marker of the file, or of the standard input for -, as it is read
+    if let Some(mode) = args.get(2) {
+        if mode != "markers" {
+            eprintln!("Unknown mode {}, expected markers", mode);
+            exit(1);
+        }
+
+        // Standard output is line buffered, so each marker shows up as it is found
+        let mut print = |marker: Marker| {
+            let kind = match marker.kind {
+                MarkerKind::Packet => "packet",
+                MarkerKind::Message => "message",
+            };
+            println!("{} {}", kind, marker.position);
+        };
+        let mut scanner = SignalScanner::default();
+        if args[1] == "-" {
+            scanner.scan(io::stdin().lock(), &mut print)?;
+        } else {
+            scanner.scan(fs::File::open(&args[1])?, &mut print)?;
+        }
+        return Ok(());
+    }
+
     let data_stream =
         fs::read_to_string(args[1].clone()).expect("Error while reading the data file");
 
     println!("part 1: {}", solve_part_1(data_stream.clone()));
-    println!("part 2: {}", solve_part_2(data_stream.clone()));
+    println!("part 2: {}", solve_part_2(data_stream));
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solve_part_1;
+    use crate::{find_marker, solve_part_1, solve_part_2};
+    use crate::scanner::{Marker, MarkerKind, SignalScanner};
 
     #[test]
     fn sample_1() {
@@ -109,4 +97,49 @@ mod tests {
 
         assert_eq!(result, 11);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn message_samples() {
+        assert_eq!(solve_part_2("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()), 19);
+        assert_eq!(solve_part_2("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()), 23);
+        assert_eq!(solve_part_2("nppdvjthqldpwncqszvftbrmjlhg".to_string()), 23);
+        assert_eq!(solve_part_2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()), 29);
+        assert_eq!(solve_part_2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()), 26);
+    }
+
+    #[test]
+    fn windows() {
+        assert_eq!(find_marker(b"aab", 1), Some(1));
+        assert_eq!(find_marker(b"aab", 2), Some(3));
+        assert_eq!(find_marker(b"aab", 3), None);
+        assert_eq!(find_marker(b"abc", 4), None);
+        assert_eq!(find_marker(b"", 0), Some(0));
+        assert_eq!(find_marker(b"abc", 0), Some(0));
+        assert_eq!(find_marker(b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ", 52), Some(52));
+    }
+
+    #[test]
+    fn streaming() {
+        let signal = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let mut markers = Vec::new();
+        let mut scanner = SignalScanner::default();
+        // Chunks split the markers, and whitespace is skipped
+        for chunk in ["mjq", "jpq\n", "mgbljsphdztnvjf", " qwrcgsmlb\n"] {
+            scanner.feed(chunk.as_bytes(), &mut |marker| markers.push(marker));
+        }
+
+        let mut from_reader = Vec::new();
+        SignalScanner::default().scan(signal.as_bytes(), &mut |marker| from_reader.push(marker)).unwrap();
+        assert_eq!(markers, from_reader);
+
+        let first = |kind| markers.iter().find(|m: &&Marker| m.kind == kind).map(|m| m.position);
+        assert_eq!(first(MarkerKind::Packet), Some(7));
+        assert_eq!(first(MarkerKind::Message), Some(19));
+
+        let packets = markers.iter().filter(|m| m.kind == MarkerKind::Packet).map(|m| m.position).collect::<Vec<_>>();
+        let expected = (4..=signal.len())
+            .filter(|&end| find_marker(&signal.as_bytes()[end - 4..end], 4).is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(packets, expected);
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::{self, ErrorKind, Read};
+
+// Sliding window over the last bytes of a stream, fed one byte at a time. It keeps a count of
+// each byte value and how many values occur more than once in the window.
+pub struct MarkerScanner {
+    window: usize,
+    last: Vec<u8>,
+    counts: [usize; 256],
+    duplicates: usize,
+    position: usize,
+}
+
+impl MarkerScanner {
+    // A marker can only end on a byte, so the window can't be empty
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "Markers need a window of at least one byte");
+        MarkerScanner {
+            window,
+            last: vec![0; window],
+            counts: [0; 256],
+            duplicates: 0,
+            position: 0,
+        }
+    }
+
+    // True when the byte ends a marker, i.e. the last `window` bytes are all different
+    pub fn push(&mut self, byte: u8) -> bool {
+        let slot = self.position % self.window;
+        if self.position >= self.window {
+            let old = self.last[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 1 {
+                self.duplicates -= 1;
+            }
+        }
+
+        self.last[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.position += 1;
+
+        self.position >= self.window && self.duplicates == 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MarkerKind {
+    Packet,
+    Message,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Marker {
+    pub kind: MarkerKind,
+    // Number of signal characters read at the end of the marker
+    pub position: usize,
+}
+
+// Start-of-packet and start-of-message markers of a signal received in chunks.
+// Whitespace, such as the newlines of a terminal, is not part of the signal.
+pub struct SignalScanner {
+    packet: MarkerScanner,
+    message: MarkerScanner,
+    position: usize,
+}
+
+impl Default for SignalScanner {
+    fn default() -> Self {
+        SignalScanner {
+            packet: MarkerScanner::new(4),
+            message: MarkerScanner::new(14),
+            position: 0,
+        }
+    }
+}
+
+impl SignalScanner {
+    pub fn feed(&mut self, chunk: &[u8], on_marker: &mut dyn FnMut(Marker)) {
+        for &byte in chunk.iter().filter(|b| !b.is_ascii_whitespace()) {
+            self.position += 1;
+            if self.packet.push(byte) {
+                on_marker(Marker { kind: MarkerKind::Packet, position: self.position });
+            }
+            if self.message.push(byte) {
+                on_marker(Marker { kind: MarkerKind::Message, position: self.position });
+            }
+        }
+    }
+
+    // Reports the markers as soon as the chunk holding them is read
+    pub fn scan(&mut self, mut reader: impl Read, on_marker: &mut dyn FnMut(Marker)) -> io::Result<()> {
+        let mut buffer = [0; 4096];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(()),
+                Ok(n) => self.feed(&buffer[..n], on_marker),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
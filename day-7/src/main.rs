@@ -1,10 +1,15 @@
+mod vfs;
+
 use std::{env, fs};
 use std::error::Error;
 use std::process::exit;
+use crate::vfs::{Filter, ROOT};
 
 #[derive(Default, Debug)]
 struct FileSystem {
-    nodes: Vec<FSNode>
+    nodes: Vec<FSNode>,
+    // Total size of the files under each node, kept up to date as nodes are added
+    sizes: Vec<usize>,
 }
 
 #[derive(Debug)]
@@ -20,137 +25,71 @@ struct FSNode {
     node_data: NodeData,
 }
 
+impl FileSystem {
+    fn add_root(&mut self) {
+        self.nodes.push(FSNode {
+            parent_node: usize::MAX,
+            name: "/".to_string(),
+            node_data: NodeData::Directory { childs: vec![] },
+        });
+        self.sizes.push(0);
+    }
+
+    // Child of the directory with this name, created when the directory doesn't have it yet.
+    // A new file adds its size to each of its ancestors, in O(depth).
+    fn entry(&mut self, dir: usize, name: &str, node_data: NodeData) -> usize {
+        if let Some(id) = self.child(dir, name) {
+            return id;
+        }
+
+        let size = match node_data {
+            NodeData::File { size } => size,
+            NodeData::Directory { .. } => 0,
+        };
+        let mut ancestor = dir;
+        loop {
+            self.sizes[ancestor] += size;
+            if ancestor == ROOT {
+                break;
+            }
+            ancestor = self.nodes[ancestor].parent_node;
+        }
+
+        self.sizes.push(size);
+        self.nodes.push(FSNode {
+            parent_node: dir,
+            name: name.to_string(),
+            node_data,
+        });
+        let new_id = self.nodes.len() - 1;
+        if let NodeData::Directory { childs } = &mut self.nodes[dir].node_data {
+            childs.push(new_id);
+        }
+        new_id
+    }
+}
+
 fn parse_data(data: String) -> FileSystem {
     let mut filesystem = FileSystem::default();
-    filesystem.nodes.push(FSNode {
-        parent_node: usize::MAX,
-        name: "/".to_string(),
-        node_data: NodeData::Directory { childs: vec![] },
-    });
+    filesystem.add_root();
     let mut current_node = 0;
 
-    let mut parsing_ls = false;
-    let mut list : Vec<&str> = Vec::new();
     for line in data.lines() {
-        let line = line.trim();
-
-        if parsing_ls && &line[0..1] != "$" {
-            list.push(line);
-        }
-        else {
-            parsing_ls = false;
-
-            for &l in &list {
-                if &l[0..1] == "d" {
-                    // Parse dir
-                    let mut found = false;
-                    match &filesystem.nodes[current_node].node_data {
-                        NodeData::File { .. } => {}
-                        NodeData::Directory { childs } => {
-                            // Check if it already exists
-                            for child_id in childs {
-                                if filesystem.nodes[*child_id].name == &l[4..] {
-                                    found = true;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-
-                    if !found {
-                        filesystem.nodes.push(FSNode {
-                            parent_node: current_node,
-                            name: l[4..].to_string(),
-                            node_data: NodeData::Directory { childs: vec![] },
-                        });
-
-                        let new_id = filesystem.nodes.len() - 1;
-                        match &mut filesystem.nodes[current_node].node_data {
-                            NodeData::File { .. } => {}
-                            NodeData::Directory { childs } => { childs.push(new_id); }
-                        }
-                    }
-                }
-                else {
-                    // Parse file
-                    let parts = l.split_ascii_whitespace().collect::<Vec<_>>();
-                    let filename = parts[1].to_string();
-                    let filesize = parts[0].parse().unwrap();
-
-                    let mut found = false;
-                    match &filesystem.nodes[current_node].node_data {
-                        NodeData::File { .. } => {}
-                        NodeData::Directory { childs } => {
-                            // Check if it already exists
-                            for child_id in childs {
-                                if filesystem.nodes[*child_id].name == filename {
-                                    found = true;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-
-                    if !found {
-                        filesystem.nodes.push(FSNode {
-                            parent_node: current_node,
-                            name: filename,
-                            node_data: NodeData::File { size: filesize },
-                        });
-
-                        let new_id = filesystem.nodes.len() - 1;
-                        match &mut filesystem.nodes[current_node].node_data {
-                            NodeData::File { .. } => {}
-                            NodeData::Directory { childs } => { childs.push(new_id); }
-                        }
-                    }
-                }
+        let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
+        match parts[..] {
+            ["$", "cd", "/"] => current_node = ROOT,
+            // As in resolve, the parent of the root is itself
+            ["$", "cd", ".."] if current_node == ROOT => {}
+            ["$", "cd", ".."] => current_node = filesystem.nodes[current_node].parent_node,
+            ["$", "cd", name] => {
+                current_node = filesystem.entry(current_node, name, NodeData::Directory { childs: vec![] });
             }
-            list.clear();
-        }
-
-        match &line[2..4] {
-            "cd" => {
-                match &line[5..] {
-                    ".." => {
-                        current_node = filesystem.nodes[current_node].parent_node;
-                    }
-                    "/" => {
-                        current_node = 0;
-                    }
-                    _ => {
-                        let mut found = false;
-                        match &filesystem.nodes[current_node].node_data {
-                            NodeData::File { .. } => {}
-                            NodeData::Directory { childs } => {
-                                for child_id in childs {
-                                    if filesystem.nodes[*child_id].name == &line[5..] {
-                                        current_node = *child_id;
-                                        found = true;
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                        if !found {
-                            filesystem.nodes.push(FSNode {
-                                parent_node: current_node,
-                                name: line[5..].to_string(),
-                                node_data: NodeData::Directory { childs: vec![] },
-                            });
-
-                            let new_id = filesystem.nodes.len() - 1;
-                            match &mut filesystem.nodes[current_node].node_data {
-                                NodeData::File { .. } => {}
-                                NodeData::Directory { childs } => { childs.push(new_id); }
-                            }
-                            current_node = new_id;
-                        }
-                    }
-                }
+            ["$", ..] => {}
+            ["dir", name] => {
+                filesystem.entry(current_node, name, NodeData::Directory { childs: vec![] });
             }
-            "ls" => {
-                parsing_ls = true;
+            [size, name] => {
+                filesystem.entry(current_node, name, NodeData::File { size: size.parse().unwrap() });
             }
             _ => {}
         }
@@ -159,19 +98,6 @@ fn parse_data(data: String) -> FileSystem {
     filesystem
 }
 
-fn get_directory_size(filesystem: &FileSystem, i: usize) -> usize {
-    match &filesystem.nodes[i].node_data {
-        NodeData::File { size } => { *size }
-        NodeData::Directory { childs } => {
-            let mut s = 0;
-            for c in childs {
-                s += get_directory_size(filesystem, *c);
-            }
-            s
-        }
-    }
-}
-
 fn solve_part_1(filesystem: &FileSystem) -> usize {
     let mut sum = 0;
 
@@ -179,7 +105,7 @@ fn solve_part_1(filesystem: &FileSystem) -> usize {
         match filesystem.nodes[n].node_data {
             NodeData::File { .. } => {}
             NodeData::Directory { .. } => {
-                let dir_size = get_directory_size(filesystem, n);
+                let dir_size = filesystem.du(n);
                 if dir_size <= 100000 {
                     sum += dir_size;
                 }
@@ -191,7 +117,7 @@ fn solve_part_1(filesystem: &FileSystem) -> usize {
 }
 
 fn solve_part_2(filesystem: &FileSystem) -> usize {
-    let used_memory = get_directory_size(filesystem, 0);
+    let used_memory = filesystem.du(ROOT);
     let available = 70000000 - used_memory;
     let to_free = 30000000 - available;
 
@@ -200,7 +126,7 @@ fn solve_part_2(filesystem: &FileSystem) -> usize {
         match filesystem.nodes[n].node_data {
             NodeData::File { .. } => {}
             NodeData::Directory { .. } => {
-                let dir_size = get_directory_size(filesystem, n);
+                let dir_size = filesystem.du(n);
                 if dir_size >= to_free && dir_size < smallest_to_delete {
                     smallest_to_delete = dir_size;
                 }
@@ -214,7 +140,7 @@ fn solve_part_2(filesystem: &FileSystem) -> usize {
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
+    if args.len() < 2 {
         eprintln!("Invalid arguments count");
         exit(1);
     }
@@ -223,19 +149,58 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let filesystem = parse_data(data);
 
-    println!("part 1: {}", solve_part_1(&filesystem));
-    println!("part 2: {}", solve_part_2(&filesystem));
+    let resolve = |path: Option<&String>| {
+        let path = path.map_or("/", |p| p.as_str());
+        filesystem.resolve(ROOT, path).ok_or_else(|| format!("No such file or directory {}", path))
+    };
+
+    match args.get(2).map(|command| command.as_str()) {
+        None => {
+            println!("part 1: {}", solve_part_1(&filesystem));
+            println!("part 2: {}", solve_part_2(&filesystem));
+        }
+        Some("ls") => {
+            let node = resolve(args.get(3))?;
+            match filesystem.list(node) {
+                Some(childs) => childs.iter().for_each(|&c| println!("{}", filesystem.nodes[c].name)),
+                None => println!("{}", filesystem.nodes[node].name),
+            }
+        }
+        Some("du") => {
+            let node = resolve(args.get(3))?;
+            println!("{}\t{}", filesystem.du(node), filesystem.path(node));
+        }
+        Some("tree") => print!("{}", filesystem.tree(resolve(args.get(3))?)),
+        // find [-name GLOB] [-min SIZE] [-max SIZE], from the root
+        Some("find") => {
+            let mut filter = Filter::default();
+            for option in args[3..].chunks(2) {
+                match option {
+                    [flag, value] if flag == "-name" => filter.name = Some(value.clone()),
+                    [flag, value] if flag == "-min" => filter.min_size = Some(value.parse()?),
+                    [flag, value] if flag == "-max" => filter.max_size = Some(value.parse()?),
+                    _ => return Err(format!("Invalid find option {}", option.join(" ")).into()),
+                }
+            }
+            for node in filesystem.find(ROOT, &filter) {
+                println!("{}\t{}", filesystem.du(node), filesystem.path(node));
+            }
+        }
+        Some(command) => {
+            eprintln!("Unknown command {}, expected ls, du, tree or find", command);
+            exit(1);
+        }
+    }
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parse_data;
+    use crate::{parse_data, solve_part_1, solve_part_2, NodeData};
+    use crate::vfs::{Filter, ROOT};
 
-    #[test]
-    fn sample_1() {
-        eprintln!("{:?}", parse_data("$ cd /
+    const SAMPLE: &str = "$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -258,6 +223,103 @@ $ ls
 8033020 d.log
 5626152 d.ext
 7214296 k
-".to_string()));
+";
+
+    #[test]
+    fn sample() {
+        let filesystem = parse_data(SAMPLE.to_string());
+
+        assert_eq!(solve_part_1(&filesystem), 95437);
+        assert_eq!(solve_part_2(&filesystem), 24933642);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn root_parent() {
+        let filesystem = parse_data("$ cd ..\n$ ls\n1 a\n".to_string());
+
+        assert_eq!(filesystem.resolve(ROOT, "/a").map(|a| filesystem.path(a)), Some("/a".to_string()));
+        assert_eq!(filesystem.du(ROOT), 1);
+    }
+
+    #[test]
+    fn paths() {
+        let filesystem = parse_data(SAMPLE.to_string());
+        let e = filesystem.resolve(ROOT, "/a/e").unwrap();
+
+        assert_eq!(filesystem.path(e), "/a/e");
+        assert_eq!(filesystem.resolve(e, "../../d/k"), filesystem.resolve(ROOT, "d/k"));
+        assert_eq!(filesystem.resolve(e, "./i"), filesystem.resolve(ROOT, "/a//e/i"));
+        assert_eq!(filesystem.resolve(e, "/../.."), Some(ROOT));
+        assert_eq!(filesystem.resolve(e, "/a/f/x"), None);
+        assert_eq!(filesystem.resolve(ROOT, "/b.txt/.."), None);
+        assert_eq!(filesystem.resolve(ROOT, "b.txt/"), filesystem.resolve(ROOT, "b.txt"));
+        assert_eq!(filesystem.path(ROOT), "/");
+
+        let names = |nodes: &[usize]| nodes.iter().map(|&n| filesystem.nodes[n].name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names(filesystem.list(ROOT).unwrap()), ["a", "b.txt", "c.dat", "d"]);
+        assert_eq!(filesystem.list(filesystem.resolve(ROOT, "/a/f").unwrap()), None);
+    }
+
+    #[test]
+    fn queries() {
+        let filesystem = parse_data(SAMPLE.to_string());
+        let du = |path| filesystem.du(filesystem.resolve(ROOT, path).unwrap());
+        assert_eq!(du("/"), 48381165);
+        assert_eq!(du("/a"), 94853);
+        assert_eq!(du("/a/e"), 584);
+        assert_eq!(du("/d"), 24933642);
+        assert_eq!(du("/d/k"), 7214296);
+
+        let find = |name: Option<&str>, min_size, max_size| {
+            let filter = Filter { name: name.map(|n| n.to_string()), min_size, max_size };
+            filesystem.find(ROOT, &filter).into_iter().map(|n| filesystem.path(n)).collect::<Vec<_>>()
+        };
+        assert_eq!(find(Some("*.*"), None, None), ["/a/h.lst", "/b.txt", "/c.dat", "/d/d.log", "/d/d.ext"]);
+        assert_eq!(find(Some("d*"), None, Some(10000000)), ["/d/d.log", "/d/d.ext"]);
+        assert_eq!(find(Some("?"), Some(29116), Some(4060174)), ["/a", "/a/f", "/d/j"]);
+        assert_eq!(find(None, Some(30000000), None), ["/"]);
+
+        assert_eq!(filesystem.tree(ROOT), "- / (dir, size=48381165)
+  - a (dir, size=94853)
+    - e (dir, size=584)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir, size=24933642)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+");
+    }
+
+    #[test]
+    fn deep_tree() {
+        let depth = 5000;
+        let mut data = "$ cd /\n".to_string();
+        for i in 0..depth {
+            data.push_str(&format!("$ ls\ndir d{}\n{} f\n$ cd d{}\n", i, i, i));
+        }
+        let filesystem = parse_data(data);
+
+        assert_eq!(filesystem.du(ROOT), depth * (depth - 1) / 2);
+        let deepest = filesystem.find(ROOT, &Filter { name: Some(format!("d{}", depth - 1)), ..Default::default() });
+        assert_eq!(filesystem.du(deepest[0]), 0);
+        // Directory dj holds the files of sizes j + 1 to depth - 1
+        let expected = (0..depth).map(|j| (j + 1..depth).sum::<usize>()).filter(|&s| s <= 100000).sum();
+        assert_eq!(solve_part_1(&filesystem), expected);
+    }
+
+    #[test]
+    fn size_cache() {
+        let mut filesystem = parse_data(SAMPLE.to_string());
+        let d = filesystem.resolve(ROOT, "/d").unwrap();
+        filesystem.entry(d, "l", NodeData::File { size: 1000 });
+        filesystem.entry(d, "m", NodeData::Directory { childs: vec![] });
+        assert_eq!(filesystem.du(ROOT), 48382165);
+        assert_eq!(filesystem.du(d), 24934642);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{FileSystem, NodeData};
+
+pub const ROOT: usize = 0;
+
+// Criteria of `find`, all of them having to match
+#[derive(Debug, Default)]
+pub struct Filter {
+    // Glob where * matches any run of characters and ? a single one
+    pub name: Option<String>,
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+}
+
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => glob_match(&pattern[1..], name) || (!name.is_empty() && glob_match(pattern, &name[1..])),
+        (Some('?'), Some(_)) => glob_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => glob_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+impl Filter {
+    fn matches(&self, name: &str, size: usize) -> bool {
+        let name_matches = self.name.as_ref().is_none_or(|pattern| {
+            glob_match(&pattern.chars().collect::<Vec<_>>(), &name.chars().collect::<Vec<_>>())
+        });
+        name_matches
+            && self.min_size.is_none_or(|min| size >= min)
+            && self.max_size.is_none_or(|max| size <= max)
+    }
+}
+
+impl FileSystem {
+    pub fn child(&self, dir: usize, name: &str) -> Option<usize> {
+        self.list(dir)?.iter().copied().find(|&id| self.nodes[id].name == name)
+    }
+
+    // Children of a directory, None for a file
+    pub fn list(&self, dir: usize) -> Option<&[usize]> {
+        match &self.nodes[dir].node_data {
+            NodeData::File { .. } => None,
+            NodeData::Directory { childs } => Some(childs),
+        }
+    }
+
+    pub fn is_directory(&self, node: usize) -> bool {
+        matches!(self.nodes[node].node_data, NodeData::Directory { .. })
+    }
+
+    // Absolute paths start from the root, the other ones from `cwd`. The parent of the root is
+    // itself, and as in a shell only directories can be followed by other components.
+    pub fn resolve(&self, cwd: usize, path: &str) -> Option<usize> {
+        let start = if path.starts_with('/') { ROOT } else { cwd };
+        path.split('/').try_fold(start, |node, component| match component {
+            "" => Some(node),
+            _ if !self.is_directory(node) => None,
+            "." => Some(node),
+            ".." => Some(if node == ROOT { ROOT } else { self.nodes[node].parent_node }),
+            name => self.child(node, name),
+        })
+    }
+
+    pub fn path(&self, node: usize) -> String {
+        if node == ROOT {
+            return "/".to_string();
+        }
+
+        let mut names = Vec::new();
+        let mut current = node;
+        while current != ROOT {
+            names.push(self.nodes[current].name.as_str());
+            current = self.nodes[current].parent_node;
+        }
+        names.iter().rev().map(|name| format!("/{}", name)).collect()
+    }
+
+    // Total size of the files under the node, like du
+    pub fn du(&self, node: usize) -> usize {
+        self.sizes[node]
+    }
+
+    // Nodes under `from`, itself included, in depth first order
+    pub fn find(&self, from: usize, filter: &Filter) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            if filter.matches(&self.nodes[node].name, self.du(node)) {
+                result.push(node);
+            }
+            if let Some(childs) = self.list(node) {
+                stack.extend(childs.iter().rev());
+            }
+        }
+        result
+    }
+
+    // Same view as the puzzle statement, children sorted by name
+    pub fn tree(&self, node: usize) -> String {
+        let mut result = String::new();
+        self.write_tree(node, 0, &mut result);
+        result
+    }
+
+    fn write_tree(&self, node: usize, depth: usize, result: &mut String) {
+        let kind = if self.is_directory(node) { "dir" } else { "file" };
+        result.push_str(&format!("{}- {} ({}, size={})\n", "  ".repeat(depth), self.nodes[node].name, kind, self.du(node)));
+
+        if let Some(childs) = self.list(node) {
+            let mut childs = childs.to_vec();
+            childs.sort_by(|&a, &b| self.nodes[a].name.cmp(&self.nodes[b].name));
+            for child in childs {
+                self.write_tree(child, depth + 1, result);
+            }
+        }
+    }
+}